@@ -0,0 +1,29 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use uwcsmini::{Alphabet, Solver, Word};
+
+fn neighbours(c: &mut Criterion) {
+    let word = Word::new("hello");
+    c.bench_function("shifts", |b| b.iter(|| black_box(word).shifts()));
+    c.bench_function("shifts_scalar", |b| {
+        b.iter(|| black_box(word).shifts_in_scalar(Alphabet::LATIN))
+    });
+    c.bench_function("rotate", |b| b.iter(|| black_box(word).rotate()));
+    c.bench_function("neighbours", |b| {
+        b.iter(|| black_box(word).neighbours(6).count())
+    });
+}
+
+/// Fixed pairs, so numbers from different runs compare. One `Solver` for
+/// all of them, as the binary does, so the big map's allocation isn't timed.
+fn solves(c: &mut Criterion) {
+    let mut solver = Solver::new();
+    let mut group = c.benchmark_group("solve");
+    for (name, left, right) in [("short", "abc", "zcad"), ("medium", "hello", "help")] {
+        let (left, right) = (Word::new(left), Word::new(right));
+        group.bench_function(name, |b| b.iter(|| solver.solve(left, right).unwrap()));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, neighbours, solves);
+criterion_main!(benches);
@@ -0,0 +1,65 @@
+//! `cargo +nightly fuzz run word_ops`: any bytes that make a word survive
+//! every move, each result still a valid packed word.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use uwcsmini::{Alphabet, Word};
+
+fn check(word: Word, len: usize) {
+    assert_eq!(Some(word), Word::from_raw_checked(word.as_u64()));
+    assert_eq!(len, usize::from(word.len()));
+    assert_eq!(Ok(word), Word::try_new(&word.to_string()));
+}
+
+fuzz_target!(|data: &[u8]| {
+    let Some((&size, text)) = data.split_first() else {
+        return;
+    };
+    let Ok(text) = std::str::from_utf8(text) else {
+        return;
+    };
+    let alphabet = Alphabet::new(size % 26 + 1);
+    let Ok(word) = Word::try_new_in(text, alphabet) else {
+        return;
+    };
+    let len = usize::from(word.len());
+    check(word, len);
+
+    for len_limit in [word.len(), 12] {
+        let code = word.get(0).unwrap();
+        let longer = [
+            word.dupl_first(len_limit),
+            word.push(code, len_limit),
+            word.insert_at(word.len(), code, len_limit),
+        ];
+        for w in longer.into_iter().flatten() {
+            check(w, len + 1);
+        }
+        for code in 1..=alphabet.size() {
+            if let Some(w) = word.insert_at(0, code, len_limit) {
+                check(w, len + 1);
+            }
+        }
+        for w in word.neighbours_in(len_limit, alphabet) {
+            check(w, usize::from(w.len()));
+            assert!(w.len().abs_diff(word.len()) <= 1);
+        }
+        for w in word.predecessors_in(len_limit, alphabet) {
+            assert!(w.neighbours_in(len_limit, alphabet).any(|n| n == word));
+        }
+    }
+    let removals = (0..word.len()).filter_map(|index| word.remove_at(index));
+    for w in word.pop().into_iter().chain(word.pop_last()).chain(removals) {
+        check(w, len - 1);
+    }
+    for w in word
+        .shifts_in(alphabet)
+        .into_iter()
+        .chain(word.rotate())
+        .chain(word.swap_adjacent())
+        .flatten()
+        .chain([word.reverse()])
+    {
+        check(w, len);
+    }
+});
@@ -0,0 +1,4614 @@
+//! Word ladders over packed lowercase words, and the searches that find them.
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::hash_map::Entry;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::{BuildHasher, BuildHasherDefault, Hasher};
+use std::mem;
+use std::num::{NonZeroU128, NonZeroU64};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use std::{fmt, fs, io};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum WordError {
+    Empty,
+    TooLong {
+        len: usize,
+    },
+    NotAscii,
+    InvalidChar {
+        ch: char,
+        index: usize,
+    },
+    /// An integer `from_raw_checked` wouldn't take.
+    NotPacked,
+}
+
+impl fmt::Display for WordError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WordError::Empty => write!(f, "empty word"),
+            WordError::TooLong { len } => write!(f, "too long: {} letters", len),
+            WordError::NotAscii => write!(f, "not ascii"),
+            WordError::InvalidChar { ch, index } => {
+                write!(f, "invalid character {:?} at {}", ch, index)
+            }
+            WordError::NotPacked => write!(f, "not a packed word"),
+        }
+    }
+}
+
+impl std::error::Error for WordError {}
+
+/// The letters words are made of: the first `size` letters of `a`-`z`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Alphabet {
+    size: u8,
+}
+
+impl Alphabet {
+    pub const LATIN: Alphabet = Alphabet { size: 26 };
+
+    pub fn new(size: u8) -> Self {
+        assert!((1..=26).contains(&size), "alphabet size {}", size);
+        Alphabet { size }
+    }
+
+    /// How many letters, from `a`.
+    pub fn size(&self) -> u8 {
+        self.size
+    }
+
+    pub fn contains(&self, ch: char) -> bool {
+        ch.is_ascii_lowercase() && (ch as u8 - b'a') < self.size
+    }
+}
+
+impl Default for Alphabet {
+    fn default() -> Self {
+        Alphabet::LATIN
+    }
+}
+
+/// How many bits each letter takes in a packed word.
+pub const BITS_PER_LETTER: u32 = 5;
+
+/// One packed letter, in the low bits: codes 1..=26, with 0 past the end.
+pub const LETTER_MASK: u8 = (1 << BITS_PER_LETTER) - 1;
+
+/// Defines a word type packing lowercase letters `BITS_PER_LETTER` apiece into
+/// `$int`, first letter in the lowest bits.
+macro_rules! packed_word {
+    ($name:ident, $nonzero:ty, $int:ty) => {
+        #[derive(Copy, Clone, Eq, PartialEq, Hash)]
+        pub struct $name($nonzero);
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                for c in self.chars() {
+                    write!(f, "{}", c)?;
+                }
+                Ok(())
+            }
+        }
+
+        impl fmt::Debug for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                fmt::Display::fmt(self, f)
+            }
+        }
+
+        /// Shorter words first, then alphabetical. The raw integer would compare
+        /// by the last letter first.
+        impl Ord for $name {
+            fn cmp(&self, other: &Self) -> Ordering {
+                self.len()
+                    .cmp(&other.len())
+                    .then_with(|| self.codes().cmp(other.codes()))
+            }
+        }
+
+        impl PartialOrd for $name {
+            fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        /// As `try_new`. There's deliberately no `From<&str>`: it'd have to panic.
+        impl TryFrom<&str> for $name {
+            type Error = WordError;
+
+            fn try_from(from: &str) -> Result<Self, Self::Error> {
+                Self::try_new(from)
+            }
+        }
+
+        /// As `from_raw_checked`.
+        impl TryFrom<$int> for $name {
+            type Error = WordError;
+
+            fn try_from(val: $int) -> Result<Self, Self::Error> {
+                Self::from_raw_checked(val).ok_or(WordError::NotPacked)
+            }
+        }
+
+        /// The packed value, as `from_raw_checked` takes it back.
+        impl From<$name> for $int {
+            fn from(word: $name) -> Self {
+                word.0.get()
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = WordError;
+
+            fn from_str(from: &str) -> Result<Self, Self::Err> {
+                Self::try_new(from)
+            }
+        }
+
+        /// As the word itself, `"abc"`, not the packed integer.
+        #[cfg(feature = "serde")]
+        impl serde::Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        /// Through `try_new`, so a bad word is an error rather than a panic.
+        #[cfg(feature = "serde")]
+        impl<'de> serde::Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let from = String::deserialize(deserializer)?;
+                Self::try_new(&from).map_err(serde::de::Error::custom)
+            }
+        }
+
+        impl $name {
+            /// As many letters as fit in the backing integer.
+            pub const MAX_LETTERS: u8 = (<$int>::BITS / BITS_PER_LETTER) as u8;
+
+            pub fn new(from: &str) -> Self {
+                match Self::try_new(from) {
+                    Ok(w) => w,
+                    Err(e) => panic!("{:?}: {}", from, e),
+                }
+            }
+
+            pub fn new_in(from: &str, alphabet: Alphabet) -> Self {
+                match Self::try_new_in(from, alphabet) {
+                    Ok(w) => w,
+                    Err(e) => panic!("{:?}: {}", from, e),
+                }
+            }
+
+            pub fn try_new(from: &str) -> Result<Self, WordError> {
+                Self::try_new_in(from, Alphabet::default())
+            }
+
+            /// Surrounding whitespace is ignored and `A`-`Z` are lowercased.
+            pub fn try_new_in(from: &str, alphabet: Alphabet) -> Result<Self, WordError> {
+                let from = from.trim_matches(|c: char| c.is_ascii_whitespace());
+                if from.is_empty() {
+                    return Err(WordError::Empty);
+                }
+                if !from.is_ascii() {
+                    return Err(WordError::NotAscii);
+                }
+                if from.len() > usize::from(Self::MAX_LETTERS) {
+                    return Err(WordError::TooLong { len: from.len() });
+                }
+                let mut w: $int = 0;
+                for (idx, c) in from.chars().enumerate() {
+                    let c = c.to_ascii_lowercase();
+                    if !alphabet.contains(c) {
+                        return Err(WordError::InvalidChar { ch: c, index: idx });
+                    }
+                    w |= (((c as u8) - b'a' + 1) as $int) << (idx as u32 * BITS_PER_LETTER);
+                }
+                Ok(Self::raw(w))
+            }
+
+            pub(crate) fn raw(val: $int) -> Self {
+                $name(<$nonzero>::new(val).unwrap())
+            }
+
+            /// Accepts only values `try_new` could have produced: every 5-bit group
+            /// a letter, with no empty group below a letter.
+            pub fn from_raw_checked(val: $int) -> Option<Self> {
+                let mut w = val;
+                let mut letters = 0;
+                while w != 0 {
+                    let c = w & <$int>::from(LETTER_MASK);
+                    if c == 0 || c > 26 {
+                        return None;
+                    }
+                    w >>= BITS_PER_LETTER;
+                    letters += 1;
+                }
+                if letters > Self::MAX_LETTERS {
+                    return None;
+                }
+                <$nonzero>::new(val).map($name)
+            }
+
+            /// The 1..=26 letter codes, in reading order.
+            pub fn codes(&self) -> impl Iterator<Item = u8> {
+                let mut w = self.0.get();
+                std::iter::from_fn(move || {
+                    if w == 0 {
+                        return None;
+                    }
+                    let c = (w & <$int>::from(LETTER_MASK)) as u8;
+                    w >>= BITS_PER_LETTER;
+                    Some(c)
+                })
+            }
+
+            pub fn chars(&self) -> impl Iterator<Item = char> {
+                self.codes().map(|c| (c + b'a' - 1) as char)
+            }
+
+            pub fn len(&self) -> u8 {
+                let w = self.0.get();
+                let first_bit_set = <$int>::BITS - w.leading_zeros() + (BITS_PER_LETTER - 1);
+                (first_bit_set / BITS_PER_LETTER) as u8
+            }
+
+            /// Never: there's always at least one letter.
+            pub fn is_empty(&self) -> bool {
+                false
+            }
+
+            /// How many positions hold different letters, for words of the same
+            /// length. Each shift fixes at most one, so it's a lower bound on the
+            /// ladder between them using shifts alone.
+            pub fn hamming(&self, other: &Self) -> Option<u32> {
+                if self.len() != other.len() {
+                    return None;
+                }
+                let diff = self.0.get() ^ other.0.get();
+                let mask = <$int>::from(LETTER_MASK);
+                let differing = (0..u32::from(self.len()))
+                    .filter(|i| (diff >> (i * BITS_PER_LETTER)) & mask != 0)
+                    .count();
+                Some(differing as u32)
+            }
+
+            /// Whether the 1..=26 `code` appears anywhere in the word.
+            pub fn contains_letter(&self, code: u8) -> bool {
+                self.codes().any(|c| c == code)
+            }
+
+            /// How many of each letter, `a` first.
+            pub fn letter_counts(&self) -> [u8; 26] {
+                let mut counts = [0; 26];
+                for c in self.codes() {
+                    counts[usize::from(c) - 1] += 1;
+                }
+                counts
+            }
+
+            /// How many letters the two words start with in common.
+            pub fn common_prefix_len(&self, other: &Self) -> u8 {
+                self.codes()
+                    .zip(other.codes())
+                    .take_while(|(a, b)| a == b)
+                    .count() as u8
+            }
+
+            /// How many letters the two words end with in common.
+            pub fn common_suffix_len(&self, other: &Self) -> u8 {
+                let (len, other_len) = (self.len(), other.len());
+                (1..=len.min(other_len))
+                    .take_while(|&i| self.get(len - i) == other.get(other_len - i))
+                    .count() as u8
+            }
+
+            /// How many letters longer one word is than the other. Every move
+            /// changes the length by at most one, so any ladder is at least this
+            /// long.
+            pub fn length_diff(&self, other: &Self) -> u32 {
+                u32::from(self.len().abs_diff(other.len()))
+            }
+
+            /// The 1..=26 code of the letter at `index`.
+            pub fn get(&self, index: u8) -> Option<u8> {
+                if index >= self.len() {
+                    return None;
+                }
+                Some(
+                    ((self.0.get() >> (u32::from(index) * BITS_PER_LETTER))
+                        & <$int>::from(LETTER_MASK)) as u8,
+                )
+            }
+
+            /// Replaces the letter at `index`. Panics if `index` is past the end, as
+            /// that would change the length, or if `code` isn't in 1..=26.
+            pub fn set(&self, index: u8, code: u8) -> Self {
+                assert!(index < self.len(), "index {} out of range", index);
+                assert!((1..=26).contains(&code), "invalid code {}", code);
+                let shift = u32::from(index) * BITS_PER_LETTER;
+                let w = self.0.get() & !(<$int>::from(LETTER_MASK) << shift);
+                Self::raw(w | <$int>::from(code) << shift)
+            }
+
+            pub fn shift_at(&self, index: u8, delta: i8) -> Option<Self> {
+                self.shift_at_in(index, delta, Alphabet::default(), true)
+            }
+
+            /// Moves the letter at `index` `delta` places along `alphabet`; one of
+            /// `shifts_in`, for a delta of 1 or -1. Without `wrap`, `None` rather
+            /// than going round past either end, as it is for a letter outside
+            /// `alphabet`.
+            pub fn shift_at_in(
+                &self,
+                index: u8,
+                delta: i8,
+                alphabet: Alphabet,
+                wrap: bool,
+            ) -> Option<Self> {
+                let code = self.get(index)?;
+                if code > alphabet.size {
+                    return None;
+                }
+                let size = i16::from(alphabet.size);
+                let moved = i16::from(code) - 1 + i16::from(delta);
+                if !wrap && !(0..size).contains(&moved) {
+                    return None;
+                }
+                Some(self.set(index, (moved.rem_euclid(size) + 1) as u8))
+            }
+
+            pub fn dupl_first(&self, len_limit: u8) -> Option<Self> {
+                let len = self.len();
+                if len >= len_limit || len >= Self::MAX_LETTERS {
+                    return None;
+                }
+                let mut w = self.0.get();
+                let s = w & <$int>::from(LETTER_MASK);
+                w <<= BITS_PER_LETTER;
+                w |= s;
+                Some(Self::raw(w))
+            }
+
+            pub fn push(&self, c: u8, len_limit: u8) -> Option<Self> {
+                debug_assert!((1..=26).contains(&c));
+                let len = self.len();
+                if len >= len_limit || len >= Self::MAX_LETTERS {
+                    return None;
+                }
+                let w = self.0.get();
+                Some(Self::raw(
+                    w | <$int>::from(c) << (u32::from(len) * BITS_PER_LETTER),
+                ))
+            }
+
+            /// Writes `code` at `index`, moving the letters from `index` onwards
+            /// along one place.
+            pub fn insert_at(&self, index: u8, code: u8, len_limit: u8) -> Option<Self> {
+                debug_assert!((1..=26).contains(&code));
+                let len = self.len();
+                if len >= len_limit || len >= Self::MAX_LETTERS || index > len {
+                    return None;
+                }
+                let w = self.0.get();
+                let shift = u32::from(index) * BITS_PER_LETTER;
+                let low = w & (((1 as $int) << shift) - 1);
+                let high = (w >> shift) << (shift + BITS_PER_LETTER);
+                Some(Self::raw(high | <$int>::from(code) << shift | low))
+            }
+
+            /// `other`'s letters after this word's.
+            pub fn append_word(&self, other: &Self, len_limit: u8) -> Option<Self> {
+                let (len, other_len) = (u32::from(self.len()), u32::from(other.len()));
+                let total = len + other_len;
+                if total > u32::from(len_limit) || total > u32::from(Self::MAX_LETTERS) {
+                    return None;
+                }
+                Some(Self::raw(
+                    self.0.get() | other.0.get() << (len * BITS_PER_LETTER),
+                ))
+            }
+
+            /// The letters from `start` up to but not including `end`. There's no
+            /// empty word, so an empty range is `None`, as is one past the end.
+            pub fn slice(&self, start: u8, end: u8) -> Option<Self> {
+                if start >= end || end > self.len() {
+                    return None;
+                }
+                let (start, end) = (
+                    u32::from(start) * BITS_PER_LETTER,
+                    u32::from(end) * BITS_PER_LETTER,
+                );
+                let below_end = (1 as $int)
+                    .checked_shl(end)
+                    .map_or(<$int>::MAX, |bit| bit - 1);
+                Some(Self::raw((self.0.get() & below_end) >> start))
+            }
+
+            pub fn pop(&self) -> Option<Self> {
+                let mut w = self.0.get();
+                w >>= BITS_PER_LETTER;
+                if w == 0 {
+                    return None;
+                }
+                Some(Self::raw(w))
+            }
+
+            pub fn pop_last(&self) -> Option<Self> {
+                let len = self.len();
+                if 1 == len {
+                    return None;
+                }
+                let w = self.0.get();
+                Some(Self::raw(
+                    w & !(<$int>::from(LETTER_MASK) << (u32::from(len - 1) * BITS_PER_LETTER)),
+                ))
+            }
+
+            /// Moves the first `n` letters to the end, or the last `-n` to the
+            /// front, wrapping round past `len`; `rotate` is `rotate_by(1)` and
+            /// `rotate_by(-1)`, bar its `None` for a single letter.
+            pub fn rotate_by(&self, n: i8) -> Self {
+                let len = self.len();
+                let k = i32::from(n).rem_euclid(i32::from(len)) as u32;
+                if 0 == k {
+                    return *self;
+                }
+                let w = self.0.get();
+                let shift = k * BITS_PER_LETTER;
+                let used = u32::from(len) * BITS_PER_LETTER;
+                let front = w & (((1 as $int) << shift) - 1);
+                Self::raw((w >> shift) | (front << (used - shift)))
+            }
+
+            /// Deletes the letter at `index`, moving the later letters back one
+            /// place; `insert_at` undone.
+            pub fn remove_at(&self, index: u8) -> Option<Self> {
+                let len = self.len();
+                if index >= len || 1 == len {
+                    return None;
+                }
+                let w = self.0.get();
+                let shift = u32::from(index) * BITS_PER_LETTER;
+                let low = w & (((1 as $int) << shift) - 1);
+                let high = (w >> (shift + BITS_PER_LETTER)) << shift;
+                Some(Self::raw(high | low))
+            }
+
+            pub fn rotate(&self) -> [Option<Self>; 2] {
+                let mask = <$int>::from(LETTER_MASK);
+
+                let len = self.len();
+
+                if 1 == len {
+                    return [None, None];
+                }
+
+                let w = self.0.get();
+
+                // Shift amounts in bits, kept as u32 so a full wide word can't
+                // overflow them. The last letter always starts below BITS.
+                let last = u32::from(len - 1) * BITS_PER_LETTER;
+                let used = u32::from(len) * BITS_PER_LETTER;
+
+                let start = w & mask;
+                let end = (w >> last) & mask;
+
+                let right = w >> BITS_PER_LETTER;
+                // When the word fills the backing integer, shifting the first
+                // letter up drops the last letter off the top by itself.
+                let left = (w << BITS_PER_LETTER) & !mask.checked_shl(used).unwrap_or(0);
+
+                [
+                    Some(Self::raw(right | (start << last))),
+                    Some(Self::raw(left | end)),
+                ]
+            }
+
+            /// Reads the same backwards, so `reverse` leaves it alone.
+            pub fn is_palindrome(&self) -> bool {
+                let len = self.len();
+                (0..len / 2).all(|i| self.get(i) == self.get(len - 1 - i))
+            }
+
+            pub fn reverse(&self) -> Self {
+                let last = usize::from(self.len()) - 1;
+                let mut w: $int = 0;
+                for (idx, c) in self.codes().enumerate() {
+                    w |= <$int>::from(c) << ((last - idx) as u32 * BITS_PER_LETTER);
+                }
+                Self::raw(w)
+            }
+
+            pub fn swap_adjacent(&self) -> [Option<Self>; $name::MAX_LETTERS as usize - 1] {
+                let w = self.0.get();
+                let mut ret = [None; $name::MAX_LETTERS as usize - 1];
+                for i in 0..usize::from(self.len()) - 1 {
+                    let shift = i as u32 * BITS_PER_LETTER;
+                    let letter = <$int>::from(LETTER_MASK);
+                    let mask = (letter << BITS_PER_LETTER | letter) << shift;
+                    let a = (w >> shift) & letter;
+                    let b = (w >> (shift + BITS_PER_LETTER)) & letter;
+                    ret[i] = Some(Self::raw(
+                        (w & !mask) | b << shift | a << (shift + BITS_PER_LETTER),
+                    ));
+                }
+                ret
+            }
+
+            pub fn shifts(&self) -> [Option<Self>; $name::MAX_LETTERS as usize * 2] {
+                self.shifts_in(Alphabet::default())
+            }
+
+            /// Each letter moved one up and one down `alphabet`, wrapping around.
+            ///
+            /// Every letter is moved at once, in the packed integer: adding a 1 to
+            /// each group can't carry, as no code is over 26, and taking one away
+            /// can't borrow, as no code is 0. Only the wraps need fixing up.
+            pub fn shifts_in(
+                &self,
+                alphabet: Alphabet,
+            ) -> [Option<Self>; $name::MAX_LETTERS as usize * 2] {
+                let letters = usize::from(Self::MAX_LETTERS);
+                let w = self.0.get();
+                let len = u32::from(self.len());
+                let used = (1 as $int)
+                    .checked_shl(len * BITS_PER_LETTER)
+                    .map_or(<$int>::MAX, |bit| bit - 1);
+                let ones = used / <$int>::from(LETTER_MASK);
+                let size = <$int>::from(alphabet.size);
+
+                let up = w + ones;
+                let past = Self::groups_over(up, alphabet.size) & ones;
+                let up = (up & !(past * <$int>::from(LETTER_MASK))) | past;
+
+                let down = w - ones;
+                let zero = ones & !Self::groups_over(down, 0);
+                let down = down | zero * size;
+
+                let (up, down) = (up ^ w, down ^ w);
+                let mut ret = [None; $name::MAX_LETTERS as usize * 2];
+                let (ups, downs) = ret.split_at_mut(letters);
+                let mut mask = <$int>::from(LETTER_MASK);
+                for (up_slot, down_slot) in ups.iter_mut().zip(downs).take(len as usize) {
+                    *up_slot = Some(Self::raw(w ^ (up & mask)));
+                    *down_slot = Some(Self::raw(w ^ (down & mask)));
+                    mask <<= BITS_PER_LETTER;
+                }
+                ret
+            }
+
+            /// Every bit a letter could be in.
+            const ALL_GROUPS: $int =
+                <$int>::MAX >> (<$int>::BITS - Self::MAX_LETTERS as u32 * BITS_PER_LETTER);
+
+            /// The low bit of each group holding more than `limit`: those that
+            /// carry out of their top bit when `31 - limit` is added. The sum's top
+            /// bits are worked out apart from the rest, so no group carries into
+            /// the next.
+            fn groups_over(w: $int, limit: u8) -> $int {
+                let ones = Self::ALL_GROUPS / <$int>::from(LETTER_MASK);
+                let tops = ones << (BITS_PER_LETTER - 1);
+                let bump = ones * <$int>::from(LETTER_MASK - limit);
+                let low_carries = (w & !tops) + (bump & !tops);
+                let carries = (w & bump) | ((w | bump) & low_carries);
+                (carries & tops) >> (BITS_PER_LETTER - 1)
+            }
+
+            /// `shifts_in` a letter at a time, kept to check the packed one against.
+            pub fn shifts_in_scalar(
+                &self,
+                alphabet: Alphabet,
+            ) -> [Option<Self>; $name::MAX_LETTERS as usize * 2] {
+                let letters = usize::from(Self::MAX_LETTERS);
+                let us = self.0.get();
+                let mut ret = [None; $name::MAX_LETTERS as usize * 2];
+                for i in 0..letters {
+                    let shift = i as u32 * BITS_PER_LETTER;
+                    let mask = <$int>::from(LETTER_MASK) << shift;
+                    let c = ((us & mask) >> shift) as u8;
+                    if c == 0 {
+                        break;
+                    }
+                    let w = us & !mask;
+                    let mut up = c + 1;
+                    let mut down = c - 1;
+                    if up > alphabet.size {
+                        up = 1;
+                    }
+
+                    if down == 0 {
+                        down = alphabet.size;
+                    }
+
+                    ret[i] = Some(Self::raw(w | <$int>::from(up) << shift));
+                    ret[i + letters] = Some(Self::raw(w | <$int>::from(down) << shift));
+                }
+                ret
+            }
+
+            pub fn predecessors(&self, len_limit: u8) -> Vec<Self> {
+                self.predecessors_in(len_limit, Alphabet::default())
+            }
+
+            /// Every word from which this one is a single forward move away,
+            /// undoing each move in turn:
+            ///
+            /// * `dupl_first` is undone by `pop`, but only if the first two letters
+            ///   match.
+            /// * `pop` is undone by putting back whichever letter was removed, so
+            ///   any letter of the alphabet may be prepended.
+            /// * the up and down `shifts` of a position undo each other, as do the
+            ///   two `rotate`s, so those sets are their own inverses. No shift
+            ///   produces a letter outside the alphabet, though.
+            /// * `reverse` and each `swap_adjacent` undo themselves.
+            ///
+            /// Duplicates are left in, as `shifts_in` and friends leave them.
+            pub fn predecessors_in(&self, len_limit: u8, alphabet: Alphabet) -> Vec<Self> {
+                let mut out = Vec::with_capacity(60);
+                if self.len() > 1 && self.get(0) == self.get(1) {
+                    out.extend(self.pop());
+                }
+                for c in 1..=alphabet.size {
+                    out.extend(self.insert_at(0, c, len_limit));
+                }
+                // A letter outside the alphabet can't have been shifted into.
+                let shifts = self.shifts_in(alphabet);
+                let letters = shifts.len() / 2;
+                for (i, op) in shifts.into_iter().enumerate() {
+                    let index = (i % letters) as u8;
+                    if self.get(index).is_some_and(|c| c <= alphabet.size) {
+                        out.extend(op);
+                    }
+                }
+                out.extend(self.rotate().into_iter().flatten());
+                out.push(self.reverse());
+                out.extend(self.swap_adjacent().into_iter().flatten());
+                out
+            }
+        }
+    };
+}
+
+packed_word!(Word, NonZeroU64, u64);
+packed_word!(WideWord, NonZeroU128, u128);
+
+impl Word {
+    pub fn as_u64(&self) -> u64 {
+        self.0.get()
+    }
+
+    /// Whether any ladder over `alphabet` from here to `other` could exist,
+    /// without looking for one. A `false` is certain; a `true` only means
+    /// there's no quick reason to give up, and says nothing of moves switched
+    /// off, forbidden words or a dictionary.
+    ///
+    /// No move grows a word past `len_limit`, or past its own length if it's
+    /// already longer, so an `other` longer than both can't be reached. No move makes a letter past the end of `alphabet` either, bar
+    /// shifting down one that's already further out, so `other` can't have a
+    /// letter beyond both `alphabet` and every letter here.
+    pub fn is_reachable(&self, other: &Word, len_limit: u8, alphabet: Alphabet) -> bool {
+        let highest = self.codes().max().unwrap_or(0).max(alphabet.size);
+        self == other
+            || (other.len() <= len_limit.max(self.len()) && other.codes().all(|c| c <= highest))
+    }
+
+    pub fn neighbours(&self, len_limit: u8) -> impl Iterator<Item = Word> {
+        self.neighbours_in(len_limit, Alphabet::default())
+    }
+
+    /// Every word one forward move away, each once, in order. Possibly
+    /// including this word itself, e.g. reversed if it's a palindrome.
+    pub fn neighbours_in(&self, len_limit: u8, alphabet: Alphabet) -> impl Iterator<Item = Word> {
+        let mut words: Vec<Word> = moves(*self, len_limit, alphabet).map(|(w, _)| w).collect();
+        words.sort();
+        words.dedup();
+        words.into_iter()
+    }
+}
+
+/// Hashes words as rustc's FxHash does: a rotate, xor and multiply per word.
+/// The packed value is already a unique key, so SipHash's resistance to
+/// crafted collisions only costs time on the solver's huge maps.
+#[derive(Default)]
+pub struct WordHasher(u64);
+
+impl Hasher for WordHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for &b in bytes {
+            self.write_u64(u64::from(b));
+        }
+    }
+
+    fn write_u64(&mut self, n: u64) {
+        self.0 = (self.0.rotate_left(5) ^ n).wrapping_mul(0x51_7c_c1_b7_27_22_0a_95);
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+/// SplitMix64: plenty random enough to shuffle a frontier, and no dependency.
+struct Rng(u64);
+
+impl Rng {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Fisher-Yates. The modulo bias is far too small to matter here.
+    fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            let j = (self.next_u64() % (i as u64 + 1)) as usize;
+            items.swap(i, j);
+        }
+    }
+}
+
+pub type WordMap<V> = HashMap<Word, V, BuildHasherDefault<WordHasher>>;
+pub type WordSet = HashSet<Word, BuildHasherDefault<WordHasher>>;
+
+/// A single move of the solver, as recorded against each word it discovers.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Op {
+    DuplFirst,
+    Pop,
+    Shift {
+        index: u8,
+        up: bool,
+    },
+    /// First letter moved to the end.
+    RotateLeft,
+    /// Last letter moved to the front.
+    RotateRight,
+    Reverse,
+    /// Letters `index` and `index + 1` exchanged.
+    Swap {
+        index: u8,
+    },
+    /// The letter at `index` deleted.
+    Remove {
+        index: u8,
+    },
+}
+
+impl Op {
+    pub fn apply(&self, word: Word, len_limit: u8, alphabet: Alphabet) -> Option<Word> {
+        match *self {
+            Op::DuplFirst => word.dupl_first(len_limit),
+            Op::Pop => word.pop(),
+            Op::Shift { index, up } => {
+                let shifts = word.shifts_in(alphabet);
+                let offset = if up { 0 } else { shifts.len() / 2 };
+                shifts[usize::from(index) + offset]
+            }
+            Op::RotateLeft => word.rotate()[0],
+            Op::RotateRight => word.rotate()[1],
+            Op::Reverse => Some(word.reverse()),
+            Op::Swap { index } => word.swap_adjacent()[usize::from(index)],
+            Op::Remove { index } => word.remove_at(index),
+        }
+    }
+
+    /// Whether this takes `word` round the end of `alphabet`: a shift up
+    /// from its last letter, or down from `a`.
+    pub fn wraps(&self, word: Word, alphabet: Alphabet) -> bool {
+        match *self {
+            Op::Shift { index, up } => {
+                word.get(index)
+                    .is_some_and(|c| if up { c >= alphabet.size } else { c == 1 })
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Positions are shown counting from one.
+impl fmt::Display for Op {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Op::DuplFirst => write!(f, "dupl"),
+            Op::Pop => write!(f, "pop"),
+            Op::Shift { index, up } => {
+                write!(f, "shift:{}{}", index + 1, if *up { '+' } else { '-' })
+            }
+            Op::RotateLeft => write!(f, "rotate"),
+            Op::RotateRight => write!(f, "unrotate"),
+            Op::Reverse => write!(f, "reverse"),
+            Op::Swap { index } => write!(f, "swap:{}", index + 1),
+            Op::Remove { index } => write!(f, "remove:{}", index + 1),
+        }
+    }
+}
+
+/// Renders a `SolveResult::reversed` ladder as `bda <-shift:2+-- bca <-rotate-- abc`.
+pub fn format_reversed_ladder(path: &[Word], ops: &[Op]) -> String {
+    let mut out = path[0].to_string();
+    for (word, op) in path[1..].iter().zip(ops) {
+        out.push_str(&format!(" <-{}-- {}", op, word));
+    }
+    out
+}
+
+/// The words along a ladder, ends included, and the move between each pair.
+pub type Ladder = (Vec<Word>, Vec<Op>);
+
+/// Renders a ladder as `abc --rotate-> bca --shift:2+-> bda`.
+pub fn format_ladder(path: &[Word], ops: &[Op]) -> String {
+    let mut out = path[0].to_string();
+    for (word, op) in path[1..].iter().zip(ops) {
+        out.push_str(&format!(" --{}-> {}", op, word));
+    }
+    out
+}
+
+/// How many levels the searches expand before giving up, unless told otherwise.
+pub const DEFAULT_MAX_STEPS: u32 = 31;
+
+/// How many words `solve` may visit before giving up, unless told otherwise.
+/// Several gigabytes of map.
+pub const DEFAULT_MAX_NODES: usize = 200_000_000;
+
+/// The most steps `Solver`'s one retry may take, unless told otherwise.
+pub const DEFAULT_RETRY_CEILING: u32 = 2 * DEFAULT_MAX_STEPS;
+
+/// The most words a visited map is sized for up front, unless told otherwise.
+/// Beyond this it grows as it needs to.
+pub const DEFAULT_MAX_CAPACITY: usize = 10_000_000;
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum SolveError {
+    /// The visited map hit its size limit while still searching.
+    Budget { explored: usize },
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SolveError::Budget { explored } => {
+                write!(f, "out of budget after exploring {} words", explored)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SolveError {}
+
+/// What each move costs `solve_weighted`; 1 apiece by default. The two
+/// rotations cost the same, as do shifts up and down, and each swap.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct OpCosts {
+    pub dupl_first: u32,
+    pub pop: u32,
+    pub shift: u32,
+    pub rotate: u32,
+    pub reverse: u32,
+    pub swap: u32,
+    pub remove: u32,
+}
+
+impl OpCosts {
+    pub fn cost(&self, op: Op) -> u32 {
+        match op {
+            Op::DuplFirst => self.dupl_first,
+            Op::Pop => self.pop,
+            Op::Shift { .. } => self.shift,
+            Op::RotateLeft | Op::RotateRight => self.rotate,
+            Op::Reverse => self.reverse,
+            Op::Swap { .. } => self.swap,
+            Op::Remove { .. } => self.remove,
+        }
+    }
+}
+
+impl Default for OpCosts {
+    fn default() -> Self {
+        OpCosts {
+            dupl_first: 1,
+            pop: 1,
+            shift: 1,
+            rotate: 1,
+            reverse: 1,
+            swap: 1,
+            remove: 1,
+        }
+    }
+}
+
+/// Which moves a search may make, and how far it may go before giving up.
+/// Everything but `remove_at` is allowed by default.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct SolverConfig {
+    /// The longest a word may grow; without one, the longer of the pair.
+    pub len_limit: Option<u8>,
+    pub max_steps: u32,
+    pub max_nodes: usize,
+    /// Caps `initial_capacity`.
+    pub max_capacity: usize,
+    /// How long `search` may take over a pair, checked between levels.
+    pub timeout: Option<Duration>,
+    pub alphabet: Alphabet,
+    pub dupl_first: bool,
+    pub pop: bool,
+    pub shifts: bool,
+    pub rotate: bool,
+    pub reverse: bool,
+    pub swaps: bool,
+    /// Deleting any letter, not just the first; off unless asked for, so
+    /// ladders stay as they always were.
+    pub remove_at: bool,
+    /// Stop at `target` reversed too, for puzzles where the two are the same.
+    pub reversed_target: bool,
+    /// Only for `solve_weighted`; the other searches count moves.
+    pub costs: OpCosts,
+    /// Shuffle each level before expanding it, so a word reachable from
+    /// several others may remember a different one, and a different shortest
+    /// ladder comes out. Without one, the same ladder every time.
+    pub seed: Option<u64>,
+    /// Search with `search_queue` rather than level by level.
+    pub queue: bool,
+    /// Let shifts go round from the end of the alphabet to the start and
+    /// back; without, `z` can't go up, nor `a` down.
+    pub wrap: bool,
+    /// When `Solver` runs out of steps, it tries once more with twice as
+    /// many, but no more than this. Anything up to `max_steps` turns that off.
+    pub retry_ceiling: u32,
+}
+
+impl SolverConfig {
+    pub fn new() -> Self {
+        SolverConfig {
+            len_limit: None,
+            max_steps: DEFAULT_MAX_STEPS,
+            max_nodes: DEFAULT_MAX_NODES,
+            max_capacity: DEFAULT_MAX_CAPACITY,
+            timeout: None,
+            alphabet: Alphabet::default(),
+            dupl_first: true,
+            pop: true,
+            shifts: true,
+            rotate: true,
+            reverse: true,
+            swaps: true,
+            remove_at: false,
+            reversed_target: false,
+            costs: OpCosts::default(),
+            seed: None,
+            queue: false,
+            wrap: true,
+            retry_ceiling: DEFAULT_RETRY_CEILING,
+        }
+    }
+
+    pub fn len_limit(mut self, len_limit: u8) -> Self {
+        self.len_limit = Some(len_limit);
+        self
+    }
+
+    pub fn max_steps(mut self, max_steps: u32) -> Self {
+        self.max_steps = max_steps;
+        self
+    }
+
+    pub fn max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = max_nodes;
+        self
+    }
+
+    pub fn max_capacity(mut self, max_capacity: usize) -> Self {
+        self.max_capacity = max_capacity;
+        self
+    }
+
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// How many words to size a visited map for: every word of up to
+    /// `len_limit` letters, if that's fewer than `max_nodes` or
+    /// `max_capacity`.
+    pub fn initial_capacity(&self, len_limit: u8) -> usize {
+        let size = usize::from(self.alphabet.size());
+        let mut words_of_len = 1usize;
+        let mut words = 0usize;
+        for _ in 0..len_limit {
+            words_of_len = words_of_len.saturating_mul(size);
+            words = words.saturating_add(words_of_len);
+        }
+        words.min(self.max_nodes).min(self.max_capacity)
+    }
+
+    pub fn alphabet(mut self, alphabet: Alphabet) -> Self {
+        self.alphabet = alphabet;
+        self
+    }
+
+    pub fn allow_dupl_first(mut self, allow: bool) -> Self {
+        self.dupl_first = allow;
+        self
+    }
+
+    pub fn allow_pop(mut self, allow: bool) -> Self {
+        self.pop = allow;
+        self
+    }
+
+    pub fn allow_shifts(mut self, allow: bool) -> Self {
+        self.shifts = allow;
+        self
+    }
+
+    /// Both directions at once.
+    pub fn allow_rotate(mut self, allow: bool) -> Self {
+        self.rotate = allow;
+        self
+    }
+
+    pub fn allow_reverse(mut self, allow: bool) -> Self {
+        self.reverse = allow;
+        self
+    }
+
+    pub fn allow_swaps(mut self, allow: bool) -> Self {
+        self.swaps = allow;
+        self
+    }
+
+    pub fn allow_wrap(mut self, allow: bool) -> Self {
+        self.wrap = allow;
+        self
+    }
+
+    pub fn allow_remove_at(mut self, allow: bool) -> Self {
+        self.remove_at = allow;
+        self
+    }
+
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    pub fn retry_ceiling(mut self, retry_ceiling: u32) -> Self {
+        self.retry_ceiling = retry_ceiling;
+        self
+    }
+
+    /// The step cap for the one retry, if there's to be one.
+    pub fn retry_steps(&self) -> Option<u32> {
+        let steps = self.max_steps.saturating_mul(2).min(self.retry_ceiling);
+        (steps > self.max_steps).then_some(steps)
+    }
+
+    pub fn use_queue(mut self, queue: bool) -> Self {
+        self.queue = queue;
+        self
+    }
+
+    pub fn accept_reversed_target(mut self, accept: bool) -> Self {
+        self.reversed_target = accept;
+        self
+    }
+
+    pub fn costs(mut self, costs: OpCosts) -> Self {
+        self.costs = costs;
+        self
+    }
+
+    /// FNV-1a over everything that decides which ladder is found, so a cached
+    /// ladder is only reused under the same rules. The node budget and the
+    /// timeout only decide whether one is found at all.
+    pub fn fingerprint(&self) -> u64 {
+        let rules = format!(
+            "{:?}",
+            SolverConfig {
+                max_nodes: 0,
+                max_capacity: 0,
+                timeout: None,
+                retry_ceiling: 0,
+                ..*self
+            }
+        );
+        rules.bytes().fold(0xcbf29ce484222325, |hash, b| {
+            (hash ^ u64::from(b)).wrapping_mul(0x100000001b3)
+        })
+    }
+
+    pub fn allows(&self, op: Op) -> bool {
+        match op {
+            Op::DuplFirst => self.dupl_first,
+            Op::Pop => self.pop,
+            Op::Shift { .. } => self.shifts,
+            Op::RotateLeft | Op::RotateRight => self.rotate,
+            Op::Reverse => self.reverse,
+            Op::Swap { .. } => self.swaps,
+            Op::Remove { .. } => self.remove_at,
+        }
+    }
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        SolverConfig::new()
+    }
+}
+
+/// Words a search may not pass through, whatever the moves allow.
+#[derive(Clone, Debug, Default)]
+pub struct WordFilter {
+    /// Treated as if they didn't exist: never visited, so never on a ladder,
+    /// even as the target.
+    pub forbidden: WordSet,
+    /// If set, the only words allowed between the endpoints.
+    pub dictionary: Option<WordSet>,
+}
+
+impl WordFilter {
+    /// Whether a search may visit `word`; an `endpoint` needn't be in the
+    /// dictionary, but can still be forbidden.
+    pub fn allows(&self, word: Word, endpoint: bool) -> bool {
+        !self.forbidden.contains(&word)
+            && (endpoint || self.dictionary.as_ref().is_none_or(|d| d.contains(&word)))
+    }
+
+    /// Lets everything through.
+    pub fn is_empty(&self) -> bool {
+        self.forbidden.is_empty() && self.dictionary.is_none()
+    }
+}
+
+/// Solves pairs one after another, reusing one big visited map between them
+/// rather than allocating afresh each time.
+pub struct Solver {
+    m: WordMap<(Word, Op)>,
+    pub config: SolverConfig,
+    /// Consulted before searching, and given every ladder found.
+    pub cache: Option<LadderCache>,
+    /// Words every search must avoid; the cache is skipped while it's set.
+    pub filter: WordFilter,
+    /// The ends of the last pair tried, and the ladder found between them.
+    last: Option<(Word, Word, Option<Ladder>)>,
+    /// Everything solved so far, added up.
+    pub totals: RunStats,
+}
+
+/// What a `Solver` has been through across every pair it's been given.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub struct RunStats {
+    pub pairs: usize,
+    pub nodes_explored: usize,
+    /// The most words any one level discovered.
+    pub peak_frontier: usize,
+    /// Time spent solving; a pair given up on doesn't count.
+    pub elapsed: Duration,
+}
+
+impl RunStats {
+    /// Adds in another solver's totals, as if one had done the lot.
+    pub fn merge(&mut self, other: RunStats) {
+        self.pairs += other.pairs;
+        self.nodes_explored += other.nodes_explored;
+        self.peak_frontier = self.peak_frontier.max(other.peak_frontier);
+        self.elapsed += other.elapsed;
+    }
+}
+
+impl Solver {
+    pub fn new() -> Self {
+        Solver::with_config(SolverConfig::new())
+    }
+
+    /// The visited map is sized per pair, as `SolverConfig::initial_capacity`
+    /// says, and never shrinks.
+    pub fn with_config(config: SolverConfig) -> Self {
+        Solver {
+            m: WordMap::default(),
+            config,
+            cache: None,
+            filter: WordFilter::default(),
+            last: None,
+            totals: RunStats::default(),
+        }
+    }
+
+    /// `solve`, with the length limit taken from the config or else from the
+    /// longer of the two words.
+    pub fn solve(&mut self, left: Word, right: Word) -> Result<SolveResult, SolveError> {
+        self.solve_with_progress(left, right, |_| {})
+    }
+
+    /// `solve`, calling `progress` after each level.
+    pub fn solve_with_progress(
+        &mut self,
+        left: Word,
+        right: Word,
+        progress: impl FnMut(LevelStat),
+    ) -> Result<SolveResult, SolveError> {
+        let result = self.solve_uncounted(left, right, progress);
+        let totals = &mut self.totals;
+        totals.pairs += 1;
+        match &result {
+            Ok(result) => {
+                totals.nodes_explored += result.nodes_explored;
+                let frontiers = result.stats.iter().map(|stat| stat.frontier);
+                totals.peak_frontier = frontiers.fold(totals.peak_frontier, usize::max);
+                totals.elapsed += result.elapsed;
+            }
+            Err(SolveError::Budget { explored }) => totals.nodes_explored += explored,
+        }
+        result
+    }
+
+    fn solve_uncounted(
+        &mut self,
+        left: Word,
+        right: Word,
+        mut progress: impl FnMut(LevelStat),
+    ) -> Result<SolveResult, SolveError> {
+        let start = Instant::now();
+        if left == right {
+            self.m.clear();
+            self.last = Some((left, right, Some((vec![left], Vec::new()))));
+            return Ok(SolveResult::same_word(left, start));
+        }
+        let len_limit = self
+            .config
+            .len_limit
+            .unwrap_or_else(|| left.len().max(right.len()));
+        // The cache doesn't know what the filter ruled out.
+        let cache = if self.filter.is_empty() {
+            self.cache.as_mut()
+        } else {
+            None
+        };
+        if let Some(cache) = &cache {
+            if let Some((path, ops)) = cache.get(left, right, len_limit, &self.config) {
+                self.m.clear();
+                self.last = Some((left, right, Some((path.clone(), ops.clone()))));
+                return Ok(SolveResult {
+                    path: Some(path),
+                    ops,
+                    ..SolveResult::not_found(Vec::new(), start)
+                });
+            }
+        }
+
+        // Only after the cache, as a cached ladder needs no second opinion. A
+        // forbidden target is never visited, so never found.
+        if !left.is_reachable(&right, len_limit, self.config.alphabet)
+            || !self.filter.allows(right, true)
+        {
+            self.m.clear();
+            self.last = Some((left, right, None));
+            return Ok(SolveResult::not_found(Vec::new(), start));
+        }
+
+        self.last = Some((left, right, None));
+        let config = self.config;
+        let mut result = Self::search(
+            &mut self.m,
+            &self.filter,
+            left,
+            right,
+            len_limit,
+            &config,
+            &mut progress,
+        )?;
+        let out_of_steps = result.levels >= config.max_steps && !result.timed_out;
+        if let (None, true, Some(steps)) = (&result.path, out_of_steps, config.retry_steps()) {
+            let config = config.max_steps(steps);
+            result = Self::search(
+                &mut self.m,
+                &self.filter,
+                left,
+                right,
+                len_limit,
+                &config,
+                &mut progress,
+            )?;
+            result.retried_at = Some(steps);
+        }
+        if let (Some(cache), Some(path)) = (cache, &result.path) {
+            cache.insert(path.clone(), &self.config);
+        }
+        let ladder = result.path.clone().map(|path| (path, result.ops.clone()));
+        self.last = Some((left, right, ladder));
+        Ok(result)
+    }
+
+    /// One search, with `config` rather than `self.config`, into a fresh map.
+    fn search(
+        m: &mut WordMap<(Word, Op)>,
+        filter: &WordFilter,
+        left: Word,
+        right: Word,
+        len_limit: u8,
+        config: &SolverConfig,
+        progress: impl FnMut(LevelStat),
+    ) -> Result<SolveResult, SolveError> {
+        // `clear` keeps the capacity.
+        m.clear();
+        m.reserve(config.initial_capacity(len_limit));
+        if config.queue {
+            search_queue(m, left, right, len_limit, config, filter)
+        } else {
+            search(m, left, right, len_limit, config, filter, progress)
+        }
+    }
+
+    /// Every word the last search reached, but its starter, with the word and
+    /// move it was first reached by. Empty after a cache hit.
+    pub fn visited(&self) -> &WordMap<(Word, Op)> {
+        &self.m
+    }
+
+    /// The word the last search first reached `word` from, one move nearer
+    /// the starter.
+    pub fn predecessor(&self, word: Word) -> Option<Word> {
+        self.m.get(&word).map(|&(from, _)| from)
+    }
+
+    /// The tree explored solving the last pair, as Graphviz DOT: an edge from
+    /// each word to every word first reached from it, labelled with the move.
+    /// With `near`, only words at most that many moves off the ladder are
+    /// kept. After a cache hit there's no tree, just the ladder.
+    pub fn dot(&self, near: Option<u32>) -> String {
+        let mut out = String::from("digraph {\n");
+        let Some((left, right, ladder)) = &self.last else {
+            out.push_str("}\n");
+            return out;
+        };
+        let mut on_ladder: WordSet = ladder.iter().flat_map(|(path, _)| path).copied().collect();
+        on_ladder.insert(*left);
+        let keep = |mut word: Word| {
+            let Some(near) = near else {
+                return true;
+            };
+            for _ in 0..=near {
+                if on_ladder.contains(&word) {
+                    return true;
+                }
+                match self.m.get(&word) {
+                    Some(&(parent, _)) => word = parent,
+                    None => return false,
+                }
+            }
+            false
+        };
+
+        let mut edges: Vec<(Word, Word, Op)> = self
+            .m
+            .iter()
+            .filter(|(&word, _)| keep(word))
+            .map(|(&word, &(parent, op))| (parent, word, op))
+            .collect();
+        if let Some((path, ops)) = ladder {
+            for (pair, &op) in path.windows(2).zip(ops) {
+                edges.push((pair[0], pair[1], op));
+            }
+        }
+        // Every word has one parent, so this only drops the ladder twice over.
+        edges.sort_by_key(|&(parent, word, _)| (word, parent));
+        edges.dedup_by_key(|&mut (_, word, _)| word);
+
+        for end in [left, right] {
+            out.push_str(&format!("  \"{}\" [shape=box];\n", end));
+        }
+        for (parent, word, op) in edges {
+            out.push_str(&format!(
+                "  \"{}\" -> \"{}\" [label=\"{}\"];\n",
+                parent, word, op
+            ));
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// Ladders found before, keyed by their ends and a fingerprint of the config
+/// they were found under. Saved as a line per ladder: the two ends, the
+/// fingerprint in hex, then every word along the ladder.
+#[derive(Debug, Default)]
+pub struct LadderCache {
+    ladders: HashMap<(Word, Word, u64), Vec<Word>>,
+}
+
+impl LadderCache {
+    /// An empty cache if there's no file yet. Lines that don't parse, or
+    /// whose ladder doesn't run from one end to the other, are dropped;
+    /// they'll just be solved again.
+    pub fn load(path: impl AsRef<Path>) -> io::Result<LadderCache> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e),
+        };
+        let mut cache = LadderCache::default();
+        for line in text.lines() {
+            let mut parts = line.split(' ');
+            let (Some(left), Some(right), Some(fingerprint)) =
+                (parts.next(), parts.next(), parts.next())
+            else {
+                continue;
+            };
+            let ends = (Word::try_new(left), Word::try_new(right));
+            let (Ok(left), Ok(right)) = ends else {
+                continue;
+            };
+            let Ok(fingerprint) = u64::from_str_radix(fingerprint, 16) else {
+                continue;
+            };
+            let Ok(path) = parts.map(Word::try_new).collect::<Result<Vec<_>, _>>() else {
+                continue;
+            };
+            if path.first() == Some(&left) && path.last() == Some(&right) {
+                cache.ladders.insert((left, right, fingerprint), path);
+            }
+        }
+        Ok(cache)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let mut entries: Vec<_> = self.ladders.iter().collect();
+        entries.sort();
+        let mut out = String::new();
+        for ((left, right, fingerprint), path) in entries {
+            out.push_str(&format!("{} {} {:x}", left, right, fingerprint));
+            for word in path {
+                out.push_str(&format!(" {}", word));
+            }
+            out.push('\n');
+        }
+        fs::write(path, out)
+    }
+
+    pub fn len(&self) -> usize {
+        self.ladders.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ladders.is_empty()
+    }
+
+    /// A ready-made ladder, and the moves along it, if each step is still a
+    /// move `config` allows.
+    pub fn get(
+        &self,
+        left: Word,
+        right: Word,
+        len_limit: u8,
+        config: &SolverConfig,
+    ) -> Option<Ladder> {
+        let path = self.ladders.get(&(left, right, config.fingerprint()))?;
+        let ops = path
+            .windows(2)
+            .map(|step| {
+                allowed_moves(step[0], len_limit, config)
+                    .find(|&(word, _)| word == step[1])
+                    .map(|(_, op)| op)
+            })
+            .collect::<Option<Vec<Op>>>()?;
+        Some((path.clone(), ops))
+    }
+
+    /// Keyed by the ends of `path`, which must have at least one word.
+    pub fn insert(&mut self, path: Vec<Word>, config: &SolverConfig) {
+        let (left, right) = (path[0], path[path.len() - 1]);
+        self.ladders
+            .insert((left, right, config.fingerprint()), path);
+    }
+}
+
+impl Default for Solver {
+    fn default() -> Self {
+        Solver::new()
+    }
+}
+
+/// How one level of a breadth-first search went.
+#[derive(Copy, Clone, Debug)]
+pub struct LevelStat {
+    pub depth: u32,
+    /// Newly discovered words.
+    pub frontier: usize,
+    /// All words seen so far.
+    pub visited: usize,
+    /// Time spent on this level alone.
+    pub elapsed: Duration,
+}
+
+/// What a search found, and what it took to find it.
+#[derive(Debug)]
+pub struct SolveResult {
+    /// The words along a shortest ladder, both ends included.
+    pub path: Option<Vec<Word>>,
+    /// The move taken between each pair of `path`; empty without one.
+    pub ops: Vec<Op>,
+    /// Words recorded in the visited map, `starter` aside.
+    pub nodes_explored: usize,
+    /// Levels expanded, which is the ladder's length in moves when one was found.
+    pub levels: u32,
+    pub elapsed: Duration,
+    pub stats: Vec<LevelStat>,
+    /// Abandoned at the config's timeout, so there may yet be a ladder.
+    pub timed_out: bool,
+    /// The step cap `Solver` tried again with, after running out of steps.
+    pub retried_at: Option<u32>,
+}
+
+impl SolveResult {
+    /// The ladder from a word to itself: no moves, and nothing explored.
+    fn same_word(word: Word, start: Instant) -> Self {
+        SolveResult {
+            path: Some(vec![word]),
+            ..Self::not_found(Vec::new(), start)
+        }
+    }
+
+    /// No ladder after the levels in `stats`, and nothing explored; a search
+    /// fills in what else it knows.
+    fn not_found(stats: Vec<LevelStat>, start: Instant) -> Self {
+        SolveResult {
+            path: None,
+            ops: Vec::new(),
+            nodes_explored: 0,
+            levels: stats.len() as u32,
+            elapsed: Instant::now() - start,
+            stats,
+            timed_out: false,
+            retried_at: None,
+        }
+    }
+
+    /// The same ladder from the target back to the starter. Each op is still
+    /// the forward move, so now `ops[i]` takes `path[i + 1]` to `path[i]`.
+    pub fn reversed(mut self) -> Self {
+        if let Some(path) = &mut self.path {
+            path.reverse();
+        }
+        self.ops.reverse();
+        self
+    }
+
+    pub fn into_ladder(self) -> Option<Ladder> {
+        let ops = self.ops;
+        self.path.map(|path| (path, ops))
+    }
+}
+
+/// Breadth-first search from `starter` for a shortest ladder to `target`.
+/// No path if there's no ladder of at most `max_steps` moves, and an error if
+/// finding out would mean visiting more than `max_nodes` words.
+pub fn solve(
+    starter: Word,
+    target: Word,
+    len_limit: u8,
+    alphabet: Alphabet,
+    max_steps: u32,
+    max_nodes: usize,
+) -> Result<SolveResult, SolveError> {
+    let config = SolverConfig::new()
+        .alphabet(alphabet)
+        .max_steps(max_steps)
+        .max_nodes(max_nodes);
+    if starter == target {
+        return Ok(SolveResult::same_word(starter, Instant::now()));
+    }
+    let capacity = config.initial_capacity(len_limit);
+    let mut m = WordMap::with_capacity_and_hasher(capacity, Default::default());
+    search(
+        &mut m,
+        starter,
+        target,
+        len_limit,
+        &config,
+        &WordFilter::default(),
+        |_| {},
+    )
+}
+
+/// `solve`, recording into `m` every word but `starter` and the
+/// word and move it was reached by, and telling `progress` about each level
+/// as it's finished.
+pub fn search<S: BuildHasher>(
+    m: &mut HashMap<Word, (Word, Op), S>,
+    starter: Word,
+    target: Word,
+    len_limit: u8,
+    config: &SolverConfig,
+    filter: &WordFilter,
+    mut progress: impl FnMut(LevelStat),
+) -> Result<SolveResult, SolveError> {
+    let start = Instant::now();
+    // `starter` is never recorded, so would never be found.
+    if starter == target {
+        return Ok(SolveResult::same_word(starter, start));
+    }
+    let goals = [
+        Some(target),
+        config.reversed_target.then(|| target.reverse()),
+    ];
+    let reached =
+        |m: &HashMap<Word, (Word, Op), S>| goals.into_iter().flatten().find(|g| m.contains_key(g));
+    let mut stats = Vec::with_capacity(32);
+    let mut timed_out = false;
+    let mut rng = config.seed.map(Rng);
+    let mut old_words: Vec<Word> = Vec::with_capacity(100);
+    let mut new_words: Vec<Word> = Vec::with_capacity(100);
+    new_words.push(starter);
+    for it in 1..=config.max_steps {
+        let level_start = Instant::now();
+        mem::swap(&mut old_words, &mut new_words);
+        new_words.clear();
+        if let Some(rng) = &mut rng {
+            rng.shuffle(&mut old_words);
+        }
+        #[cfg(feature = "parallel")]
+        let candidates = expand_parallel(&old_words, len_limit, config);
+        #[cfg(not(feature = "parallel"))]
+        let candidates = old_words.iter().flat_map(|&k| {
+            allowed_moves(k, len_limit, config).map(move |(word, op)| (word, k, op))
+        });
+
+        for (word, k, op) in candidates {
+            if word == starter
+                || !config.allows(op)
+                || !filter.allows(word, goals.contains(&Some(word)))
+            {
+                continue;
+            }
+            if m.len() >= config.max_nodes && !m.contains_key(&word) {
+                return Err(SolveError::Budget { explored: m.len() });
+            }
+            if let Entry::Vacant(v) = m.entry(word) {
+                v.insert((k, op));
+                new_words.push(word);
+            }
+        }
+
+        // Only vacant entries are pushed, so this can't find anything today,
+        // but the frontier must stay a set however the expansion is split up.
+        new_words.sort();
+        new_words.dedup();
+
+        let stat = LevelStat {
+            depth: it,
+            frontier: new_words.len(),
+            visited: m.len(),
+            elapsed: Instant::now() - level_start,
+        };
+        stats.push(stat);
+        progress(stat);
+
+        if reached(m).is_some() || new_words.is_empty() {
+            break;
+        }
+        if config
+            .timeout
+            .is_some_and(|timeout| start.elapsed() >= timeout)
+        {
+            timed_out = true;
+            break;
+        }
+    }
+
+    let Some(reached) = reached(m) else {
+        return Ok(SolveResult {
+            nodes_explored: m.len(),
+            timed_out,
+            ..SolveResult::not_found(stats, start)
+        });
+    };
+
+    let (path, ops) = trace(m, reached);
+    Ok(SolveResult {
+        path: Some(path),
+        ops,
+        nodes_explored: m.len(),
+        ..SolveResult::not_found(stats, start)
+    })
+}
+
+/// The ladder to `reached`, following `m` back to the word that has no entry.
+fn trace<S: BuildHasher>(m: &HashMap<Word, (Word, Op), S>, reached: Word) -> Ladder {
+    let mut path = Vec::with_capacity(32);
+    let mut ops = Vec::with_capacity(32);
+    let mut curr = reached;
+    path.push(curr);
+    while let Some(&(word, op)) = m.get(&curr) {
+        path.push(word);
+        ops.push(op);
+        curr = word;
+    }
+
+    path.reverse();
+    ops.reverse();
+    (path, ops)
+}
+
+/// `search` from a single first-in first-out queue instead of a level at a
+/// time, stopping as soon as a goal is seen rather than at the end of its
+/// level. The queue only ever spans two levels, and no second frontier is
+/// built beside it, so it peaks lower, but there are no `LevelStat`s to
+/// report and `seed` is ignored. The ladders are as short.
+pub fn search_queue<S: BuildHasher>(
+    m: &mut HashMap<Word, (Word, Op), S>,
+    starter: Word,
+    target: Word,
+    len_limit: u8,
+    config: &SolverConfig,
+    filter: &WordFilter,
+) -> Result<SolveResult, SolveError> {
+    let start = Instant::now();
+    if starter == target {
+        return Ok(SolveResult::same_word(starter, start));
+    }
+    let goals = [
+        Some(target),
+        config.reversed_target.then(|| target.reverse()),
+    ];
+    let mut timed_out = false;
+    let mut levels = 0;
+    let mut reached = None;
+    let mut queue = VecDeque::with_capacity(100);
+    queue.push_back((starter, 0));
+    'search: while let Some((k, depth)) = queue.pop_front() {
+        if depth >= config.max_steps {
+            break;
+        }
+        // A level is done, so this is where `search` would look at the clock.
+        if depth == levels {
+            if depth > 0
+                && config
+                    .timeout
+                    .is_some_and(|timeout| start.elapsed() >= timeout)
+            {
+                timed_out = true;
+                break;
+            }
+            levels += 1;
+        }
+        for (word, op) in allowed_moves(k, len_limit, config) {
+            if word == starter || !filter.allows(word, goals.contains(&Some(word))) {
+                continue;
+            }
+            if m.len() >= config.max_nodes && !m.contains_key(&word) {
+                return Err(SolveError::Budget { explored: m.len() });
+            }
+            if let Entry::Vacant(v) = m.entry(word) {
+                v.insert((k, op));
+                if goals.contains(&Some(word)) {
+                    reached = Some(word);
+                    break 'search;
+                }
+                queue.push_back((word, depth + 1));
+            }
+        }
+    }
+
+    let Some(reached) = reached else {
+        return Ok(SolveResult {
+            nodes_explored: m.len(),
+            levels,
+            timed_out,
+            ..SolveResult::not_found(Vec::new(), start)
+        });
+    };
+    let (path, ops) = trace(m, reached);
+    Ok(SolveResult {
+        path: Some(path),
+        ops,
+        nodes_explored: m.len(),
+        levels,
+        ..SolveResult::not_found(Vec::new(), start)
+    })
+}
+
+/// Every forward move from `word` and the word it leads to, possibly with
+/// repeats.
+pub fn moves(word: Word, len_limit: u8, alphabet: Alphabet) -> impl Iterator<Item = (Word, Op)> {
+    let shifts = word.shifts_in(alphabet);
+    let letters = shifts.len() / 2;
+    let [left, right] = word.rotate();
+    [
+        (word.dupl_first(len_limit), Op::DuplFirst),
+        (word.pop(), Op::Pop),
+    ]
+    .into_iter()
+    .chain(shifts.into_iter().enumerate().map(move |(i, w)| {
+        let index = (i % letters) as u8;
+        (
+            w,
+            Op::Shift {
+                index,
+                up: i < letters,
+            },
+        )
+    }))
+    .chain([
+        (left, Op::RotateLeft),
+        (right, Op::RotateRight),
+        (Some(word.reverse()), Op::Reverse),
+    ])
+    .chain(
+        word.swap_adjacent()
+            .into_iter()
+            .enumerate()
+            .map(|(index, w)| (w, Op::Swap { index: index as u8 })),
+    )
+    .filter_map(|(w, op)| Some((w?, op)))
+}
+
+/// `moves`, and removals where `config` asks for them, less any move it
+/// turns off, wrapping shifts included.
+pub fn allowed_moves(
+    word: Word,
+    len_limit: u8,
+    config: &SolverConfig,
+) -> impl Iterator<Item = (Word, Op)> + '_ {
+    let removals = (0..word.len())
+        .filter(|_| config.remove_at)
+        .filter_map(move |index| Some((word.remove_at(index)?, Op::Remove { index })));
+    moves(word, len_limit, config.alphabet)
+        .chain(removals)
+        .filter(move |&(_, op)| {
+            config.allows(op) && (config.wrap || !op.wraps(word, config.alphabet))
+        })
+}
+
+/// `allowed_moves` from each of `words` as `(word, from, op)`, in the same order as
+/// working through `words` one at a time. Chunks are expanded on the rayon
+/// pool and only stitched back together here, so the caller can still insert
+/// into its map from a single thread.
+#[cfg(feature = "parallel")]
+pub fn expand_parallel(
+    words: &[Word],
+    len_limit: u8,
+    config: &SolverConfig,
+) -> Vec<(Word, Word, Op)> {
+    use rayon::prelude::*;
+
+    let chunks: Vec<Vec<(Word, Word, Op)>> = words
+        .par_chunks(1024)
+        .map(|chunk| {
+            let mut out = Vec::with_capacity(chunk.len() * 40);
+            for &k in chunk {
+                out.extend(allowed_moves(k, len_limit, config).map(|(word, op)| (word, k, op)));
+            }
+            out
+        })
+        .collect();
+    chunks.concat()
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum PathError {
+    Empty,
+    /// No single move takes `path[index]` to `path[index + 1]`.
+    Disconnected {
+        index: usize,
+        from: Word,
+        to: Word,
+    },
+}
+
+impl fmt::Display for PathError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathError::Empty => write!(f, "empty path"),
+            PathError::Disconnected { index, from, to } => {
+                write!(f, "no move from {} to {} at step {}", from, to, index)
+            }
+        }
+    }
+}
+
+impl std::error::Error for PathError {}
+
+/// Checks each word of `path` is one forward move on from the last.
+pub fn verify_path(path: &[Word], len_limit: u8, alphabet: Alphabet) -> Result<(), PathError> {
+    if path.is_empty() {
+        return Err(PathError::Empty);
+    }
+    for (index, step) in path.windows(2).enumerate() {
+        let (from, to) = (step[0], step[1]);
+        if !moves(from, len_limit, alphabet).any(|(word, _)| word == to) {
+            return Err(PathError::Disconnected { index, from, to });
+        }
+    }
+    Ok(())
+}
+
+/// The words from `start` to `target`, following `preds` from each word to
+/// the one it was reached from. `None` if `target` was never reached, or if
+/// the chain breaks off or comes back round on itself short of `start`.
+pub fn reconstruct_path<S: BuildHasher>(
+    preds: &HashMap<Word, Word, S>,
+    start: Word,
+    target: Word,
+) -> Option<Vec<Word>> {
+    let mut path = vec![target];
+    let mut curr = target;
+    while curr != start {
+        // A chain that doesn't repeat a word has no more links than `preds`.
+        if path.len() > preds.len() {
+            return None;
+        }
+        curr = *preds.get(&curr)?;
+        path.push(curr);
+    }
+    path.reverse();
+    Some(path)
+}
+
+/// Breadth-first search from both ends at once, growing whichever frontier is
+/// smaller by a level at a time, and stopping when they meet. The backwards
+/// search walks `Word::predecessors_in`, so the ladder is still made of forward moves.
+pub fn solve_bidirectional(
+    left: Word,
+    right: Word,
+    len_limit: u8,
+    alphabet: Alphabet,
+) -> Option<Vec<Word>> {
+    if left == right {
+        return Some(vec![left]);
+    }
+
+    // `forward` maps each word to the word it was reached from, and `backward`
+    // to the word it leads to; each end maps to itself.
+    let mut forward = WordMap::default();
+    let mut backward = WordMap::default();
+    forward.insert(left, left);
+    backward.insert(right, right);
+    let mut forward_words = vec![left];
+    let mut backward_words = vec![right];
+
+    let mut meet = None;
+    for _ in 1..=DEFAULT_MAX_STEPS {
+        if forward_words.is_empty() || backward_words.is_empty() {
+            break;
+        }
+        let grow_forward = forward_words.len() <= backward_words.len();
+        let (words, seen, other) = if grow_forward {
+            (&mut forward_words, &mut forward, &backward)
+        } else {
+            (&mut backward_words, &mut backward, &forward)
+        };
+
+        let old_words = mem::take(words);
+        for k in old_words {
+            let expanded = if grow_forward {
+                k.neighbours_in(len_limit, alphabet).collect()
+            } else {
+                k.predecessors_in(len_limit, alphabet)
+            };
+            for word in expanded {
+                if let Entry::Vacant(v) = seen.entry(word) {
+                    v.insert(k);
+                    words.push(word);
+                    // Frontiers only touch once the shortest ladder would cross
+                    // them, so the first meeting is as good as any.
+                    if meet.is_none() && other.contains_key(&word) {
+                        meet = Some(word);
+                    }
+                }
+            }
+        }
+
+        if meet.is_some() {
+            break;
+        }
+    }
+
+    let meet = meet?;
+    let mut path = reconstruct_path(&forward, left, meet)?;
+    let mut rest = reconstruct_path(&backward, right, meet)?;
+    rest.pop();
+    path.extend(rest.into_iter().rev());
+    Some(path)
+}
+
+/// A lower bound on the moves from `from` to `to`. Only `shifts`, `pop` and
+/// `dupl_first` change which letters a word holds, and each swaps, removes or
+/// adds a single one, so at least this many letters of `from` must go and this
+/// many of `to` must arrive. Counting mismatched positions instead would
+/// overestimate, as a single `rotate` can move every letter.
+pub fn letter_mismatch(from: Word, to: Word) -> u32 {
+    let mut counts = [0i32; 27];
+    for c in from.codes() {
+        counts[usize::from(c)] += 1;
+    }
+    for c in to.codes() {
+        counts[usize::from(c)] -= 1;
+    }
+    let surplus: i32 = counts.iter().filter(|&&n| n > 0).sum();
+    let deficit: i32 = -counts.iter().filter(|&&n| n < 0).sum::<i32>();
+    surplus.max(deficit) as u32
+}
+
+/// Breadth-first search from `left` until any of `targets` turns up, for the
+/// nearest of them and a shortest ladder to it. Ties go to whichever comes
+/// first in `targets`.
+pub fn solve_nearest(
+    left: Word,
+    targets: &[Word],
+    len_limit: u8,
+    alphabet: Alphabet,
+) -> Option<(Word, Vec<Word>)> {
+    // Each word and the word it was reached from; `left` maps to itself.
+    let mut seen = WordMap::default();
+    seen.insert(left, left);
+    let mut new_words = vec![left];
+    let mut found = targets.iter().find(|t| seen.contains_key(t));
+    for _ in 1..=DEFAULT_MAX_STEPS {
+        if found.is_some() || new_words.is_empty() {
+            break;
+        }
+        for k in mem::take(&mut new_words) {
+            for word in k.neighbours_in(len_limit, alphabet) {
+                if let Entry::Vacant(v) = seen.entry(word) {
+                    v.insert(k);
+                    new_words.push(word);
+                }
+            }
+        }
+        found = targets.iter().find(|t| seen.contains_key(t));
+    }
+
+    let &target = found?;
+    Some((target, reconstruct_path(&seen, left, target)?))
+}
+
+/// Breadth-first search from `left` that stops as soon as it reaches any word
+/// within two moves of `right`, found up front with `Word::predecessors_in`,
+/// and stitches on the rest. The first level to touch that set holds a word
+/// on a shortest ladder, so of what it touches the word nearest `right` makes
+/// one: usually a level or two sooner than `solve` would stop.
+pub fn solve_mitm(left: Word, right: Word, len_limit: u8, alphabet: Alphabet) -> Option<Vec<Word>> {
+    // Each word near `right`, the word it leads to on the way there, and how
+    // many moves away it is; `right` maps to itself.
+    let mut tail: WordMap<(Word, u32)> = WordMap::default();
+    tail.insert(right, (right, 0));
+    let mut ring = vec![right];
+    for depth in 1..=2 {
+        for k in mem::take(&mut ring) {
+            for word in k.predecessors_in(len_limit, alphabet) {
+                if let Entry::Vacant(v) = tail.entry(word) {
+                    v.insert((k, depth));
+                    ring.push(word);
+                }
+            }
+        }
+    }
+
+    // Each word and the word it was reached from; `left` maps to itself.
+    let mut seen = WordMap::default();
+    seen.insert(left, left);
+    let mut new_words = vec![left];
+    let mut touch = tail.contains_key(&left).then_some(left);
+    for _ in 1..=DEFAULT_MAX_STEPS {
+        if touch.is_some() || new_words.is_empty() {
+            break;
+        }
+        for k in mem::take(&mut new_words) {
+            for word in k.neighbours_in(len_limit, alphabet) {
+                if let Entry::Vacant(v) = seen.entry(word) {
+                    v.insert(k);
+                    new_words.push(word);
+                    if let Some(&(_, depth)) = tail.get(&word) {
+                        if touch.is_none_or(|t| depth < tail[&t].1) {
+                            touch = Some(word);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let touch = touch?;
+    let mut path = reconstruct_path(&seen, left, touch)?;
+    let mut curr = touch;
+    while curr != right {
+        curr = tail[&curr].0;
+        path.push(curr);
+    }
+    Some(path)
+}
+
+/// A* search guided by `letter_mismatch`, which never overestimates and
+/// changes by at most one per move, so the first time `right` is popped off
+/// the heap its ladder is a shortest one.
+pub fn solve_astar(
+    left: Word,
+    right: Word,
+    len_limit: u8,
+    alphabet: Alphabet,
+) -> Option<Vec<Word>> {
+    let mut came_from: WordMap<Word> = WordMap::default();
+    let mut best: WordMap<u32> = WordMap::default();
+    let mut heap = BinaryHeap::new();
+    best.insert(left, 0);
+    heap.push(Reverse((letter_mismatch(left, right), 0, left)));
+
+    while let Some(Reverse((_, g, k))) = heap.pop() {
+        if k == right {
+            return reconstruct_path(&came_from, left, right);
+        }
+        if best[&k] < g || g >= DEFAULT_MAX_STEPS {
+            continue;
+        }
+        for word in k.neighbours_in(len_limit, alphabet) {
+            let cost = g + 1;
+            if best.get(&word).is_some_and(|&known| known <= cost) {
+                continue;
+            }
+            best.insert(word, cost);
+            came_from.insert(word, k);
+            heap.push(Reverse((cost + letter_mismatch(word, right), cost, word)));
+        }
+    }
+    None
+}
+
+/// Dijkstra's algorithm over the moves `config` allows, each costing what
+/// `config.costs` says, returning a cheapest ladder and its total cost. With
+/// every cost 1 that's just a shortest ladder, which `solve` finds faster.
+/// There's no step cap: a cheap enough ladder may be long.
+pub fn solve_weighted(
+    left: Word,
+    right: Word,
+    len_limit: u8,
+    config: &SolverConfig,
+) -> Option<(Ladder, u32)> {
+    let mut came_from: WordMap<(Word, Op)> = WordMap::default();
+    let mut best: WordMap<u32> = WordMap::default();
+    let mut heap = BinaryHeap::new();
+    best.insert(left, 0);
+    heap.push(Reverse((0, left)));
+
+    while let Some(Reverse((cost, k))) = heap.pop() {
+        if k == right {
+            let mut path = vec![right];
+            let mut ops = Vec::new();
+            let mut curr = right;
+            while let Some(&(word, op)) = came_from.get(&curr) {
+                path.push(word);
+                ops.push(op);
+                curr = word;
+            }
+            path.reverse();
+            ops.reverse();
+            return Some(((path, ops), cost));
+        }
+        if best[&k] < cost {
+            continue;
+        }
+        for (word, op) in allowed_moves(k, len_limit, config) {
+            if word == left {
+                continue;
+            }
+            let cost = cost + config.costs.cost(op);
+            if best.get(&word).is_some_and(|&known| known <= cost) {
+                continue;
+            }
+            best.insert(word, cost);
+            came_from.insert(word, (k, op));
+            heap.push(Reverse((cost, word)));
+        }
+    }
+    None
+}
+
+/// How many different shortest ladders lead from `left` to `right`, counting
+/// ladders as sequences of words, so two moves with the same result are one.
+/// Zero if `right` can't be reached within the step cap. The flag is set if
+/// some count overflowed, and was held at `u64::MAX`.
+pub fn count_shortest_paths(
+    left: Word,
+    right: Word,
+    len_limit: u8,
+    alphabet: Alphabet,
+) -> (u64, bool) {
+    // Each word's depth and how many shortest ladders reach it.
+    let mut m: WordMap<(u32, u64)> = WordMap::default();
+    m.insert(left, (0, 1));
+    let mut saturated = false;
+
+    let mut new_words = vec![left];
+    for it in 1..=DEFAULT_MAX_STEPS {
+        if m.contains_key(&right) || new_words.is_empty() {
+            break;
+        }
+        let old_words = mem::take(&mut new_words);
+        for k in old_words {
+            let paths = m[&k].1;
+            for word in k.neighbours_in(len_limit, alphabet) {
+                match m.entry(word) {
+                    Entry::Vacant(v) => {
+                        v.insert((it, paths));
+                        new_words.push(word);
+                    }
+                    Entry::Occupied(mut o) => {
+                        let (depth, count) = o.get_mut();
+                        if *depth == it {
+                            *count = count.checked_add(paths).unwrap_or_else(|| {
+                                saturated = true;
+                                u64::MAX
+                            });
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    let count = m.get(&right).map_or(0, |&(_, count)| count);
+    (count, saturated)
+}
+
+/// The number of moves on a shortest ladder, without keeping the ladder.
+pub fn distance(left: Word, right: Word, len_limit: u8, alphabet: Alphabet) -> Option<u32> {
+    if left == right {
+        return Some(0);
+    }
+    let mut seen = WordSet::default();
+    seen.insert(left);
+    let mut new_words = vec![left];
+    for it in 1..=DEFAULT_MAX_STEPS {
+        let old_words = mem::take(&mut new_words);
+        for k in old_words {
+            for word in k.neighbours_in(len_limit, alphabet) {
+                if word == right {
+                    return Some(it);
+                }
+                if seen.insert(word) {
+                    new_words.push(word);
+                }
+            }
+        }
+        if new_words.is_empty() {
+            break;
+        }
+    }
+    None
+}
+
+/// The word furthest from `start`, and how far that is, found by searching
+/// until nothing new turns up or the step cap is hit. Ties go to the first
+/// word in `Word`'s order.
+pub fn farthest_from(start: Word, len_limit: u8, alphabet: Alphabet) -> (Word, u32) {
+    let mut seen = WordSet::default();
+    seen.insert(start);
+    let mut farthest = (start, 0);
+    let mut new_words = vec![start];
+    for it in 1..=DEFAULT_MAX_STEPS {
+        let old_words = mem::take(&mut new_words);
+        for k in old_words {
+            for word in k.neighbours_in(len_limit, alphabet) {
+                if seen.insert(word) {
+                    new_words.push(word);
+                }
+            }
+        }
+        match new_words.iter().min() {
+            Some(&word) => farthest = (word, it),
+            None => break,
+        }
+    }
+    farthest
+}
+
+pub fn words_at_distance(start: Word, k: u32, len_limit: u8) -> Vec<Word> {
+    words_at_distance_in(start, k, len_limit, Alphabet::default())
+}
+
+/// Every word whose shortest ladder from `start` is exactly `k` moves, in the
+/// order the search first reached them.
+pub fn words_at_distance_in(start: Word, k: u32, len_limit: u8, alphabet: Alphabet) -> Vec<Word> {
+    let mut seen = WordSet::default();
+    seen.insert(start);
+    let mut new_words = vec![start];
+    for _ in 1..=k {
+        let old_words = mem::take(&mut new_words);
+        for from in old_words {
+            for word in from.neighbours_in(len_limit, alphabet) {
+                if seen.insert(word) {
+                    new_words.push(word);
+                }
+            }
+        }
+        if new_words.is_empty() {
+            break;
+        }
+    }
+    new_words
+}
+
+/// `distance` between every ordered pair of `words`: row `i` holds the
+/// distances from `words[i]`. `None` where there's no ladder within the step
+/// cap.
+///
+/// That's one breadth-first search per word, each stopping once it has seen
+/// every word or run out, so it costs about as much as `words.len()` calls to
+/// `distance` for the furthest pair from each word. Fine for a handful of
+/// words; a big list wants something smarter.
+pub fn distance_matrix(words: &[Word], len_limit: u8, alphabet: Alphabet) -> Vec<Vec<Option<u32>>> {
+    words
+        .iter()
+        .map(|&from| {
+            let mut row = vec![None; words.len()];
+            // Each word still to find, and where it goes in the row.
+            let mut wanted: WordMap<Vec<usize>> = WordMap::default();
+            for (j, &to) in words.iter().enumerate() {
+                wanted.entry(to).or_default().push(j);
+            }
+            for j in wanted.remove(&from).unwrap_or_default() {
+                row[j] = Some(0);
+            }
+
+            let mut seen = WordSet::default();
+            seen.insert(from);
+            let mut new_words = vec![from];
+            for it in 1..=DEFAULT_MAX_STEPS {
+                if wanted.is_empty() || new_words.is_empty() {
+                    break;
+                }
+                let old_words = mem::take(&mut new_words);
+                for k in old_words {
+                    for word in k.neighbours_in(len_limit, alphabet) {
+                        if seen.insert(word) {
+                            for j in wanted.remove(&word).unwrap_or_default() {
+                                row[j] = Some(it);
+                            }
+                            new_words.push(word);
+                        }
+                    }
+                }
+            }
+            row
+        })
+        .collect()
+}
+
+#[test]
+fn solves() {
+    let (path, _) = solve(
+        Word::new("ab"),
+        Word::new("ca"),
+        2,
+        Alphabet::default(),
+        DEFAULT_MAX_STEPS,
+        DEFAULT_MAX_NODES,
+    )
+    .unwrap()
+    .into_ladder()
+    .unwrap();
+    assert_eq!(
+        vec![Word::new("ab"), Word::new("ac"), Word::new("ca")],
+        path
+    );
+}
+
+#[test]
+fn solve_known_path() {
+    let (path, ops) = solve(
+        Word::new("abc"),
+        Word::new("zcad"),
+        4,
+        Alphabet::default(),
+        DEFAULT_MAX_STEPS,
+        DEFAULT_MAX_NODES,
+    )
+    .unwrap()
+    .into_ladder()
+    .unwrap();
+    assert_eq!(
+        "abc --shift:3+-> abd --shift:2+-> acd --dupl-> aacd --swap:2-> acad --shift:1--> zcad",
+        format_ladder(&path, &ops)
+    );
+}
+
+#[test]
+fn word_map_paths() {
+    for (left, right) in [("abc", "zcad"), ("cat", "dog"), ("hello", "lo")] {
+        let (left, right) = (Word::new(left), Word::new(right));
+        let len_limit = left.len().max(right.len());
+        let mut std_map = HashMap::new();
+        let mut word_map = WordMap::default();
+        let std_found = search(
+            &mut std_map,
+            left,
+            right,
+            len_limit,
+            &SolverConfig::new(),
+            &WordFilter::default(),
+            |_| {},
+        );
+        let word_found = search(
+            &mut word_map,
+            left,
+            right,
+            len_limit,
+            &SolverConfig::new(),
+            &WordFilter::default(),
+            |_| {},
+        );
+        let (std_found, word_found) = (std_found.unwrap(), word_found.unwrap());
+        assert_eq!(std_found.path, word_found.path);
+        assert_eq!(std_found.ops, word_found.ops);
+    }
+}
+
+#[test]
+fn no_wrap() {
+    let wrapping = SolverConfig::new();
+    let flat = wrapping.allow_wrap(false);
+    let neighbours = |word: &str, config: &SolverConfig| -> HashSet<Word> {
+        allowed_moves(Word::new(word), 3, config)
+            .map(|(word, _)| word)
+            .collect()
+    };
+    for (word, lost) in [
+        ("azm", vec!["zzm", "aam"]),
+        ("za", vec!["aa", "zz"]),
+        ("mmm", vec![]),
+        ("a", vec!["z"]),
+    ] {
+        let all = neighbours(word, &wrapping);
+        let kept = neighbours(word, &flat);
+        let lost: HashSet<Word> = lost.into_iter().map(Word::new).collect();
+        assert_eq!(lost, &all - &kept, "{}", word);
+        assert!(kept.is_subset(&all));
+    }
+
+    let five = Alphabet::new(5);
+    let e = Word::new("e");
+    let shift = |up| Op::Shift { index: 0, up };
+    assert!(shift(true).wraps(e, five));
+    assert!(!shift(false).wraps(e, five));
+    assert!(!shift(true).wraps(e, Alphabet::default()));
+    assert!(shift(false).wraps(Word::new("a"), five));
+    assert!(!Op::Reverse.wraps(e, five));
+
+    // a to z is one step round the end, and the long way without.
+    let (a, z) = (Word::new("a"), Word::new("z"));
+    let steps = |config: SolverConfig| {
+        Solver::with_config(config)
+            .solve(a, z)
+            .unwrap()
+            .into_ladder()
+            .unwrap()
+            .1
+            .len()
+    };
+    assert_eq!(1, steps(wrapping));
+    assert_eq!(25, steps(flat));
+}
+
+#[test]
+fn retries() {
+    // Five moves apart, so three steps is too few and double that enough.
+    let (left, right) = (Word::new("abc"), Word::new("bzz"));
+    let config = SolverConfig::new().max_steps(3).len_limit(3);
+    for queue in [false, true] {
+        let config = config.use_queue(queue);
+        let result = Solver::with_config(config).solve(left, right).unwrap();
+        assert_eq!(Some(6), result.retried_at);
+        assert_eq!(6, result.path.unwrap().len());
+
+        let result = Solver::with_config(config.retry_ceiling(4))
+            .solve(left, right)
+            .unwrap();
+        assert_eq!(Some(4), result.retried_at);
+        assert_eq!(None, result.path);
+
+        let result = Solver::with_config(config.retry_ceiling(3))
+            .solve(left, right)
+            .unwrap();
+        assert_eq!(None, result.retried_at);
+        assert_eq!(None, result.path);
+    }
+
+    // No retry when the search ran out of words, not steps.
+    let result = Solver::with_config(SolverConfig::new().alphabet(Alphabet::new(2)))
+        .solve(Word::new("a"), Word::new("b"))
+        .unwrap();
+    assert_eq!(None, result.retried_at);
+    let result = Solver::with_config(SolverConfig::new())
+        .solve(Word::new("ab"), Word::new("ba"))
+        .unwrap();
+    assert_eq!(None, result.retried_at);
+
+    assert_eq!(Some(62), SolverConfig::new().retry_steps());
+    assert_eq!(Some(40), SolverConfig::new().max_steps(20).retry_steps());
+    assert_eq!(None, SolverConfig::new().max_steps(62).retry_steps());
+}
+
+#[test]
+fn queue_paths() {
+    for (left, right) in [
+        ("abc", "zcad"),
+        ("cat", "dog"),
+        ("hello", "lo"),
+        ("ab", "bc"),
+        ("abc", "bzz"),
+    ] {
+        let (left, right) = (Word::new(left), Word::new(right));
+        let ladder = |config: SolverConfig| {
+            let result = Solver::with_config(config).solve(left, right).unwrap();
+            (result.levels, result.into_ladder().unwrap())
+        };
+        let (levels, (path, _)) = ladder(SolverConfig::new());
+        let (queue_levels, (queue_path, queue_ops)) = ladder(SolverConfig::new().use_queue(true));
+        assert_eq!(path.len(), queue_path.len(), "{:?}", queue_path);
+        assert_eq!(levels, queue_levels);
+        assert_eq!(
+            (left, right),
+            (queue_path[0], queue_path[queue_path.len() - 1])
+        );
+        for (pair, op) in queue_path.windows(2).zip(queue_ops) {
+            assert_eq!(Some(pair[1]), op.apply(pair[0], 4, Alphabet::default()));
+        }
+    }
+
+    let config = SolverConfig::new().use_queue(true);
+    let mut solver = Solver::with_config(config.max_steps(1));
+    assert_eq!(
+        None,
+        solver
+            .solve(Word::new("abc"), Word::new("zzz"))
+            .unwrap()
+            .path
+    );
+    let mut solver = Solver::with_config(config.timeout(Duration::ZERO));
+    let result = solver.solve(Word::new("abc"), Word::new("zzz")).unwrap();
+    assert!(result.timed_out);
+    assert_eq!(1, result.levels);
+    let mut solver = Solver::with_config(config.max_nodes(10));
+    assert!(matches!(
+        solver.solve(Word::new("abc"), Word::new("zzz")),
+        Err(SolveError::Budget { .. })
+    ));
+}
+
+/// `cargo test --release bench_maps -- --ignored --nocapture`
+#[test]
+#[ignore]
+fn bench_maps() {
+    let config = SolverConfig::new();
+    let filter = WordFilter::default();
+    let (left, right) = (Word::new("sick"), Word::new("true"));
+    let start = Instant::now();
+    search(
+        &mut HashMap::new(),
+        left,
+        right,
+        4,
+        &config,
+        &filter,
+        |_| {},
+    )
+    .unwrap();
+    let std_time = Instant::now() - start;
+    let start = Instant::now();
+    search(
+        &mut WordMap::default(),
+        left,
+        right,
+        4,
+        &config,
+        &filter,
+        |_| {},
+    )
+    .unwrap();
+    let word_time = Instant::now() - start;
+    println!("std: {:?}, word: {:?}", std_time, word_time);
+}
+
+#[test]
+fn solver_reuse() {
+    let mut solver = Solver::new();
+    let (path, _) = solver
+        .solve(Word::new("hello"), Word::new("lo"))
+        .unwrap()
+        .into_ladder()
+        .unwrap();
+    assert_eq!(4, path.len());
+    assert_eq!(Some(&Word::new("hello")), path.first());
+    assert_eq!(Some(&Word::new("lo")), path.last());
+    let capacity = solver.m.capacity();
+
+    // A smaller pair reuses the map it's been given, rather than shrinking it.
+    let (path, _) = solver
+        .solve(Word::new("abc"), Word::new("bca"))
+        .unwrap()
+        .into_ladder()
+        .unwrap();
+    assert_eq!(vec![Word::new("abc"), Word::new("bca")], path);
+    assert_eq!(capacity, solver.m.capacity());
+}
+
+#[test]
+fn configured_ops() {
+    let (left, right) = (Word::new("abc"), Word::new("bca"));
+    let small = SolverConfig::new().max_nodes(100_000);
+    let (path, _) = Solver::with_config(small)
+        .solve(left, right)
+        .unwrap()
+        .into_ladder()
+        .unwrap();
+    assert_eq!(2, path.len());
+
+    let mut solver = Solver::with_config(small.allow_rotate(false));
+    let (path, ops) = solver.solve(left, right).unwrap().into_ladder().unwrap();
+    assert!(path.len() > 2, "{}", format_ladder(&path, &ops));
+    assert!(ops
+        .iter()
+        .all(|op| !matches!(op, Op::RotateLeft | Op::RotateRight)));
+
+    // Only shifts can't change the length.
+    let shifts_only = small
+        .allow_dupl_first(false)
+        .allow_pop(false)
+        .allow_rotate(false)
+        .allow_reverse(false)
+        .allow_swaps(false);
+    let mut solver = Solver::with_config(shifts_only);
+    assert_eq!(None, solver.solve(left, Word::new("ab")).unwrap().path);
+    assert_eq!(
+        None,
+        Solver::with_config(shifts_only.len_limit(4))
+            .solve(left, Word::new("abcd"))
+            .unwrap()
+            .path
+    );
+}
+
+#[test]
+fn reversed_target() {
+    // abd is one shift from abc, which is cba backwards, but two moves from cba.
+    let (left, right) = (Word::new("abd"), Word::new("cba"));
+    let config = SolverConfig::new().max_nodes(100_000);
+    let path = Solver::with_config(config)
+        .solve(left, right)
+        .unwrap()
+        .path
+        .unwrap();
+    assert_eq!(3, path.len());
+    assert_eq!(Some(&right), path.last());
+
+    let path = Solver::with_config(config.accept_reversed_target(true))
+        .solve(left, right)
+        .unwrap()
+        .path
+        .unwrap();
+    assert_eq!(vec![left, Word::new("abc")], path);
+}
+
+#[test]
+fn frontier_dedup() {
+    // Rotating, reversing and swapping ab all give ba, and with only two
+    // letters each shift up is also the shift down. (A shift and a rotation
+    // can never agree: rotating changes a word in at least two places.)
+    let (left, right) = (Word::new("ab"), Word::new("zzz"));
+    let two = Alphabet::new(2);
+    let result = solve(left, right, 2, two, 1, DEFAULT_MAX_NODES).unwrap();
+    assert_eq!(9, moves(left, 2, two).count());
+    // b, aa, bb and ba
+    assert_eq!(4, result.stats[0].frontier);
+    assert_eq!(4, result.nodes_explored);
+}
+
+#[test]
+fn cache_reuse() {
+    let path = std::env::temp_dir().join(format!("uwcsmini-cache-{}.txt", std::process::id()));
+    let _ = fs::remove_file(&path);
+    let (left, right) = (Word::new("abc"), Word::new("zcad"));
+    let config = SolverConfig::new().max_nodes(100_000);
+
+    let mut solver = Solver::with_config(config);
+    solver.cache = Some(LadderCache::load(&path).unwrap());
+    let first = solver.solve(left, right).unwrap();
+    assert!(first.nodes_explored > 0);
+    solver.cache.as_ref().unwrap().save(&path).unwrap();
+
+    let mut solver = Solver::with_config(config);
+    solver.cache = Some(LadderCache::load(&path).unwrap());
+    assert_eq!(1, solver.cache.as_ref().unwrap().len());
+    let second = solver.solve(left, right).unwrap();
+    assert_eq!(0, second.nodes_explored);
+    assert!(second.stats.is_empty());
+    assert_eq!(first.path, second.path);
+    assert_eq!(first.ops, second.ops);
+
+    // Different rules, different ladders.
+    let no_swaps = config.allow_swaps(false);
+    assert_ne!(config.fingerprint(), no_swaps.fingerprint());
+    assert_eq!(config.fingerprint(), config.max_nodes(5).fingerprint());
+    solver.config = no_swaps;
+    let third = solver.solve(left, right).unwrap();
+    assert!(third.nodes_explored > 0);
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn cache_wrong_ends() {
+    let path = std::env::temp_dir().join(format!("uwcsmini-ends-{}.txt", std::process::id()));
+    fs::write(&path, "ab bc 0 ab bb bc\nab bc 1 ab bb\nab bc 2 bb bc\n").unwrap();
+    let cache = LadderCache::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+    assert_eq!(1, cache.len());
+}
+
+#[test]
+fn forbidden_words() {
+    // abd is the only way to abe in two moves.
+    let (left, right) = (Word::new("abc"), Word::new("abe"));
+    let mut solver = Solver::with_config(SolverConfig::new().max_nodes(100_000));
+    let path = solver.solve(left, right).unwrap().path.unwrap();
+    assert_eq!(vec![left, Word::new("abd"), right], path);
+
+    solver.filter.forbidden.insert(Word::new("abd"));
+    let (path, ops) = solver.solve(left, right).unwrap().into_ladder().unwrap();
+    assert!(path.len() > 3, "{}", format_ladder(&path, &ops));
+    assert!(!path.contains(&Word::new("abd")));
+
+    solver.filter.forbidden.insert(right);
+    assert_eq!(None, solver.solve(left, right).unwrap().path);
+}
+
+#[test]
+fn dictionary() {
+    let (left, right) = (Word::new("abc"), Word::new("abf"));
+    let dictionary = |words: &[&str]| Some(words.iter().map(|w| Word::new(w)).collect());
+    let mut solver = Solver::with_config(SolverConfig::new().max_nodes(100_000));
+    solver.filter.dictionary = dictionary(&["abd", "zzz"]);
+    assert_eq!(None, solver.solve(left, right).unwrap().path);
+
+    // Nothing else is a word, so the last letter has to walk all the way.
+    solver.filter.dictionary = dictionary(&["abd", "abe"]);
+    let path = solver.solve(left, right).unwrap().path.unwrap();
+    let expected: Vec<Word> = ["abc", "abd", "abe", "abf"].map(Word::new).into();
+    assert_eq!(expected, path);
+}
+
+#[test]
+fn dot_graphs() {
+    let mut solver = Solver::with_config(SolverConfig::new().max_nodes(100_000));
+    assert_eq!("digraph {\n}\n", solver.dot(None));
+
+    let (left, right) = (Word::new("abc"), Word::new("bca"));
+    solver.solve(left, right).unwrap();
+    let dot = solver.dot(None);
+    let lines: Vec<&str> = dot.lines().collect();
+    assert_eq!(Some(&"digraph {"), lines.first());
+    assert_eq!(Some(&"}"), lines.last());
+    let body = &lines[1..lines.len() - 1];
+    assert_eq!(
+        ["  \"abc\" [shape=box];", "  \"bca\" [shape=box];"],
+        body[..2]
+    );
+    for line in &body[2..] {
+        let (edge, label) = line.split_once(" [label=\"").unwrap();
+        let (from, to) = edge.trim().split_once(" -> ").unwrap();
+        for word in [from, to] {
+            let word = word.strip_prefix('"').unwrap().strip_suffix('"').unwrap();
+            Word::try_new(word).unwrap();
+        }
+        assert!(label.ends_with("\"];"), "{}", line);
+    }
+    assert!(body.contains(&"  \"abc\" -> \"bca\" [label=\"rotate\"];"));
+    assert_eq!(solver.m.len() + 2, body.len());
+
+    let near = solver.dot(Some(0));
+    assert_eq!(5, near.lines().count(), "{}", near);
+}
+
+#[test]
+fn longer_than_both_ends() {
+    let (left, right) = (Word::new("abc"), Word::new("bzz"));
+    let mut solver = Solver::with_config(SolverConfig::new().max_nodes(1_000_000));
+    let (path, _) = solver.solve(left, right).unwrap().into_ladder().unwrap();
+    assert_eq!(6, path.len());
+    assert!(path.iter().all(|w| w.len() <= 3));
+
+    solver.config = solver.config.len_limit(4);
+    let (path, ops) = solver.solve(left, right).unwrap().into_ladder().unwrap();
+    assert_eq!(5, path.len(), "{}", format_ladder(&path, &ops));
+    assert!(path.iter().any(|w| w.len() == 4));
+}
+
+#[test]
+fn same_word() {
+    let abc = Word::new("abc");
+    let result = solve(
+        abc,
+        abc,
+        3,
+        Alphabet::LATIN,
+        DEFAULT_MAX_STEPS,
+        DEFAULT_MAX_NODES,
+    )
+    .unwrap();
+    assert_eq!(Some(vec![abc]), result.path);
+    assert_eq!(0, result.nodes_explored);
+    assert_eq!(0, result.levels);
+
+    let mut solver = Solver::with_config(SolverConfig::new().max_nodes(1));
+    let result = solver.solve(abc, Word::new("ABC")).unwrap();
+    assert_eq!((vec![abc], Vec::new()), result.into_ladder().unwrap());
+
+    let mut m = WordMap::default();
+    let config = SolverConfig::new();
+    let result = search(&mut m, abc, abc, 3, &config, &WordFilter::default(), |_| {}).unwrap();
+    assert_eq!(Some(vec![abc]), result.path);
+    assert!(m.is_empty());
+}
+
+#[test]
+fn merged_totals() {
+    let mut totals = RunStats {
+        pairs: 2,
+        nodes_explored: 10,
+        peak_frontier: 7,
+        elapsed: Duration::from_millis(3),
+    };
+    totals.merge(RunStats {
+        pairs: 1,
+        nodes_explored: 5,
+        peak_frontier: 4,
+        elapsed: Duration::from_millis(2),
+    });
+    assert_eq!(
+        RunStats {
+            pairs: 3,
+            nodes_explored: 15,
+            peak_frontier: 7,
+            elapsed: Duration::from_millis(5),
+        },
+        totals
+    );
+}
+
+#[test]
+fn run_totals() {
+    let mut solver = Solver::with_config(SolverConfig::new().max_nodes(100_000));
+    let first = solver.solve(Word::new("abc"), Word::new("abf")).unwrap();
+    let second = solver.solve(Word::new("ab"), Word::new("zz")).unwrap();
+    let totals = solver.totals;
+    assert_eq!(2, totals.pairs);
+    assert_eq!(
+        first.nodes_explored + second.nodes_explored,
+        totals.nodes_explored
+    );
+    let peak = first
+        .stats
+        .iter()
+        .chain(&second.stats)
+        .map(|s| s.frontier)
+        .max();
+    assert_eq!(peak, Some(totals.peak_frontier));
+    assert_eq!(first.elapsed + second.elapsed, totals.elapsed);
+
+    solver.config = solver.config.max_nodes(10);
+    let Err(SolveError::Budget { explored }) = solver.solve(Word::new("abc"), Word::new("zzz"))
+    else {
+        panic!("should run out");
+    };
+    assert_eq!(3, solver.totals.pairs);
+    assert_eq!(
+        totals.nodes_explored + explored,
+        solver.totals.nodes_explored
+    );
+}
+
+#[test]
+fn initial_capacities() {
+    let config = SolverConfig::new();
+    assert_eq!(26 + 26 * 26, config.initial_capacity(2));
+    assert_eq!(DEFAULT_MAX_CAPACITY, config.initial_capacity(12));
+    assert_eq!(
+        5 + 25 + 125,
+        config.alphabet(Alphabet::new(5)).initial_capacity(3)
+    );
+    assert_eq!(100, config.max_nodes(100).initial_capacity(4));
+    assert_eq!(1000, config.max_capacity(1000).initial_capacity(4));
+
+    let mut solver = Solver::new();
+    let (path, _) = solver
+        .solve(Word::new("ab"), Word::new("ba"))
+        .unwrap()
+        .into_ladder()
+        .unwrap();
+    assert_eq!(vec![Word::new("ab"), Word::new("ba")], path);
+    assert!(solver.m.capacity() < 10_000, "{}", solver.m.capacity());
+}
+
+#[test]
+fn reversed_results() {
+    let (left, right) = (Word::new("abc"), Word::new("bda"));
+    let mut solver = Solver::with_config(SolverConfig::new().max_nodes(100_000));
+    let forward = solver.solve(left, right).unwrap();
+    let (path, ops) = forward.into_ladder().unwrap();
+    let backward = solver.solve(left, right).unwrap().reversed();
+    let (back_path, back_ops) = backward.into_ladder().unwrap();
+    assert_eq!(path.iter().rev().copied().collect::<Vec<_>>(), back_path);
+    assert_eq!(ops.iter().rev().copied().collect::<Vec<_>>(), back_ops);
+    assert_eq!(Some(&right), back_path.first());
+    for (pair, op) in back_path.windows(2).zip(&back_ops) {
+        assert_eq!(Some(pair[0]), op.apply(pair[1], 3, Alphabet::LATIN));
+    }
+
+    let ends = (
+        vec![right, Word::new("bca"), left],
+        vec![Op::Shift { index: 1, up: true }, Op::RotateLeft],
+    );
+    assert_eq!(
+        "bda <-shift:2+-- bca <-rotate-- abc",
+        format_reversed_ladder(&ends.0, &ends.1)
+    );
+    solver.config = solver.config.max_steps(1);
+    let none = solver.solve(left, Word::new("zzz")).unwrap();
+    assert_eq!(None, none.reversed().path);
+}
+
+#[test]
+fn timeouts() {
+    let (left, right) = (Word::new("abc"), Word::new("bda"));
+    let config = SolverConfig::new()
+        .max_nodes(100_000)
+        .timeout(Duration::ZERO);
+    let mut solver = Solver::with_config(config);
+    let result = solver.solve(left, right).unwrap();
+    assert!(result.timed_out);
+    assert_eq!(None, result.path);
+    assert_eq!(1, result.levels);
+    assert_eq!(config.fingerprint(), SolverConfig::new().fingerprint());
+
+    // Found on the first level, before the clock's looked at.
+    let result = solver.solve(left, Word::new("bca")).unwrap();
+    assert!(!result.timed_out);
+    assert_eq!(2, result.path.unwrap().len());
+
+    solver.config = solver.config.timeout(Duration::from_secs(60));
+    let result = solver.solve(left, right).unwrap();
+    assert!(!result.timed_out);
+    assert_eq!(3, result.path.unwrap().len());
+}
+
+#[test]
+fn predecessors_after_solving() {
+    let (left, right) = (Word::new("abc"), Word::new("zcad"));
+    let mut solver = Solver::with_config(SolverConfig::new().max_nodes(1_000_000));
+    let path = solver.solve(left, right).unwrap().path.unwrap();
+    for pair in path.windows(2) {
+        assert_eq!(Some(pair[0]), solver.predecessor(pair[1]));
+    }
+    assert_eq!(None, solver.predecessor(left));
+
+    // Off the ladder too: every visited word is a move from its predecessor,
+    // and a move nearer the start.
+    for (&word, &(from, op)) in solver.visited().iter().take(50) {
+        assert_eq!(Some(word), op.apply(from, 4, Alphabet::LATIN));
+        let near = distance(left, from, 4, Alphabet::LATIN).unwrap();
+        assert_eq!(Some(near + 1), distance(left, word, 4, Alphabet::LATIN));
+    }
+}
+
+#[test]
+fn solve_result() {
+    let (left, right) = (Word::new("abc"), Word::new("bca"));
+    let latin = Alphabet::default();
+    let result = solve(left, right, 3, latin, DEFAULT_MAX_STEPS, DEFAULT_MAX_NODES).unwrap();
+    assert_eq!(Some(vec![left, right]), result.path);
+    assert_eq!(vec![Op::RotateLeft], result.ops);
+    assert_eq!(1, result.levels);
+    let first_level: WordSet = left
+        .neighbours_in(3, latin)
+        .filter(|&w| w != left)
+        .collect();
+    assert_eq!(first_level.len(), result.nodes_explored);
+
+    let result = solve(
+        left,
+        Word::new("zcad"),
+        4,
+        latin,
+        DEFAULT_MAX_STEPS,
+        DEFAULT_MAX_NODES,
+    )
+    .unwrap();
+    assert_eq!(6, result.path.unwrap().len());
+    assert_eq!(5, result.levels);
+    assert_eq!(result.stats.last().unwrap().visited, result.nodes_explored);
+}
+
+#[test]
+fn progress_callback() {
+    let mut seen = Vec::new();
+    let mut solver = Solver::with_config(SolverConfig::new().max_nodes(100_000));
+    let result = solver
+        .solve_with_progress(Word::new("abc"), Word::new("zcad"), |stat| {
+            seen.push(stat.depth)
+        })
+        .unwrap();
+    assert_eq!(vec![1, 2, 3, 4, 5], seen);
+    assert_eq!(result.levels as usize, seen.len());
+
+    // Levels that find nothing new still count.
+    let mut calls = 0;
+    solver.config = solver
+        .config
+        .alphabet(Alphabet::new(5))
+        .allow_dupl_first(false);
+    let result = solver
+        .solve_with_progress(Word::new("a"), Word::new("aa"), |_| calls += 1)
+        .unwrap();
+    assert_eq!(None, result.path);
+    assert_eq!(3, calls);
+}
+
+#[test]
+fn level_stats() {
+    let searched = solve(
+        Word::new("abc"),
+        Word::new("zcad"),
+        4,
+        Alphabet::default(),
+        DEFAULT_MAX_STEPS,
+        DEFAULT_MAX_NODES,
+    )
+    .unwrap();
+    let path = searched.path.unwrap();
+    assert_eq!(path.len() - 1, searched.stats.len());
+    assert_eq!(searched.levels as usize, searched.stats.len());
+    for (i, stat) in searched.stats.iter().enumerate() {
+        assert_eq!(i as u32 + 1, stat.depth);
+    }
+    assert!(searched
+        .stats
+        .windows(2)
+        .all(|w| w[0].visited < w[1].visited));
+}
+
+#[test]
+fn solve_ops() {
+    let latin = Alphabet::default();
+    let (path, ops) = solve(
+        Word::new("abc"),
+        Word::new("bca"),
+        3,
+        latin,
+        DEFAULT_MAX_STEPS,
+        DEFAULT_MAX_NODES,
+    )
+    .unwrap()
+    .into_ladder()
+    .unwrap();
+    assert_eq!(vec![Op::RotateLeft], ops);
+    assert_eq!("abc --rotate-> bca", format_ladder(&path, &ops));
+
+    let (_, ops) = solve(
+        Word::new("bca"),
+        Word::new("bda"),
+        3,
+        latin,
+        DEFAULT_MAX_STEPS,
+        DEFAULT_MAX_NODES,
+    )
+    .unwrap()
+    .into_ladder()
+    .unwrap();
+    assert_eq!(vec![Op::Shift { index: 1, up: true }], ops);
+
+    let (path, ops) = solve(
+        Word::new("abc"),
+        Word::new("zcad"),
+        4,
+        latin,
+        DEFAULT_MAX_STEPS,
+        DEFAULT_MAX_NODES,
+    )
+    .unwrap()
+    .into_ladder()
+    .unwrap();
+    assert_eq!(path.len(), ops.len() + 1);
+    for (i, op) in ops.iter().enumerate() {
+        assert_eq!(Some(path[i + 1]), op.apply(path[i], 4, latin), "{}", op);
+    }
+}
+
+#[test]
+fn op_labels() {
+    assert_eq!("shift:2+", Op::Shift { index: 1, up: true }.to_string());
+    assert_eq!(
+        "shift:1-",
+        Op::Shift {
+            index: 0,
+            up: false
+        }
+        .to_string()
+    );
+    assert_eq!("swap:3", Op::Swap { index: 2 }.to_string());
+}
+
+/// Every word of `1..=max_len` letters from `alphabet`.
+#[cfg(test)]
+fn all_words(max_len: u8, alphabet: Alphabet) -> Vec<Word> {
+    let mut words: Vec<Word> = (1..=alphabet.size)
+        .map(|c| Word::raw(u64::from(c)))
+        .collect();
+    let mut last = words.clone();
+    for _ in 1..max_len {
+        last = last
+            .iter()
+            .flat_map(|w| (1..=alphabet.size).filter_map(move |c| w.push(c, max_len)))
+            .collect();
+        words.extend(&last);
+    }
+    words
+}
+
+/// Every move from `word` done the slow way, on strings, as something to check
+/// the bit twiddling against.
+#[cfg(test)]
+fn slow_neighbours(word: &str, len_limit: u8) -> std::collections::BTreeSet<String> {
+    let letters: Vec<char> = word.chars().collect();
+    let len = letters.len();
+    let mut out = std::collections::BTreeSet::new();
+    let mut add = |letters: &[char]| out.insert(letters.iter().collect::<String>());
+
+    if len < usize::from(len_limit) {
+        let mut dupl = letters.clone();
+        dupl.insert(0, letters[0]);
+        add(&dupl);
+    }
+    if len > 1 {
+        add(&letters[1..]);
+    }
+    for i in 0..len {
+        for step in [1, 25] {
+            let mut shifted = letters.clone();
+            shifted[i] = (b'a' + (letters[i] as u8 - b'a' + step) % 26) as char;
+            add(&shifted);
+        }
+    }
+    let mut rotated = letters.clone();
+    rotated.rotate_left(1);
+    add(&rotated);
+    let mut rotated = letters.clone();
+    rotated.rotate_right(1);
+    add(&rotated);
+    let mut reversed = letters.clone();
+    reversed.reverse();
+    add(&reversed);
+    for i in 1..len {
+        let mut swapped = letters.clone();
+        swapped.swap(i - 1, i);
+        add(&swapped);
+    }
+    out
+}
+
+#[test]
+fn neighbour_counts() {
+    // pop, six shifts, two rotations, reverse and two swaps
+    assert_eq!(12, Word::new("abc").neighbours(3).count());
+    // and room to duplicate
+    assert_eq!(13, Word::new("abc").neighbours(4).count());
+    // aaa, a, ba, za, ab, az, and aa itself from rotating, reversing or swapping
+    assert_eq!(7, Word::new("aa").neighbours(3).count());
+    assert!(Word::new("aa").neighbours(3).any(|w| w == Word::new("aa")));
+}
+
+#[test]
+fn neighbours_match_strings() {
+    let check = |word: Word, len_limit: u8| {
+        let fast: std::collections::BTreeSet<String> = word
+            .neighbours_in(len_limit, Alphabet::LATIN)
+            .map(|w| w.to_string())
+            .collect();
+        assert_eq!(
+            slow_neighbours(&word.to_string(), len_limit),
+            fast,
+            "{} within {}",
+            word,
+            len_limit
+        );
+    };
+
+    // Everything short, and longer words over the letters where wrapping and
+    // the edges of the word are most likely to go wrong.
+    for word in all_words(3, Alphabet::LATIN) {
+        check(word, 3);
+        check(word, 4);
+    }
+    let edges = [1, 2, 13, 25, 26];
+    let mut words: Vec<Word> = edges.iter().map(|&c| Word::raw(u64::from(c))).collect();
+    for len in 2..=5 {
+        let longer: Vec<Word> = words
+            .iter()
+            .filter(|w| w.len() == len - 1)
+            .flat_map(|w| edges.iter().filter_map(move |&c| w.push(c, len)))
+            .collect();
+        words.extend(longer);
+    }
+    for word in words {
+        check(word, 5);
+    }
+    check(Word::new("abcdef"), 6);
+    check(Word::new("abcdefghijkl"), 12);
+}
+
+#[test]
+fn predecessors_brute_force() {
+    for (max_len, alphabet) in [
+        (3, Alphabet::new(3)),
+        (4, Alphabet::new(2)),
+        (2, Alphabet::LATIN),
+    ] {
+        let words = all_words(max_len, alphabet);
+        let mut expected: WordMap<WordSet> = WordMap::default();
+        for &from in &words {
+            for to in from.neighbours_in(max_len, alphabet) {
+                expected.entry(to).or_default().insert(from);
+            }
+        }
+        for &word in &words {
+            let found: WordSet = word
+                .predecessors_in(max_len, alphabet)
+                .into_iter()
+                .collect();
+            assert_eq!(
+                expected.remove(&word).unwrap_or_default(),
+                found,
+                "{} within {}",
+                word,
+                max_len
+            );
+        }
+        assert!(expected.is_empty());
+    }
+}
+
+#[test]
+fn predecessors_latin() {
+    let word = Word::new("aab");
+    // undoing a `dupl_first`
+    assert!(word.predecessors(3).contains(&Word::new("ab")));
+    // undoing a `pop`, given room for the extra letter
+    assert!(!word.predecessors(3).contains(&Word::new("zaab")));
+    assert!(word.predecessors(4).contains(&Word::new("zaab")));
+    // first letters differ, so no `dupl_first` could have made it
+    assert!(!Word::new("ab").predecessors(2).contains(&Word::new("b")));
+}
+
+#[test]
+fn bidirectional() {
+    let latin = Alphabet::default();
+    assert_eq!(
+        Some(vec![Word::new("a")]),
+        solve_bidirectional(Word::new("a"), Word::new("a"), 1, latin)
+    );
+    for (left, right) in [
+        ("ab", "ca"),
+        ("abc", "bda"),
+        ("abcd", "dcab"),
+        ("cat", "dog"),
+        ("ab", "zzz"),
+        ("hello", "lo"),
+    ] {
+        let (left, right) = (Word::new(left), Word::new(right));
+        let len_limit = left.len().max(right.len());
+        let (expected, _) = solve(
+            left,
+            right,
+            len_limit,
+            latin,
+            DEFAULT_MAX_STEPS,
+            DEFAULT_MAX_NODES,
+        )
+        .unwrap()
+        .into_ladder()
+        .unwrap();
+        let path = solve_bidirectional(left, right, len_limit, latin).unwrap();
+        assert_eq!(expected.len(), path.len(), "{} -> {}", left, right);
+        assert_eq!(Some(&left), path.first());
+        assert_eq!(Some(&right), path.last());
+        for step in path.windows(2) {
+            assert!(step[0]
+                .neighbours_in(len_limit, latin)
+                .any(|w| w == step[1]));
+        }
+    }
+}
+
+#[test]
+fn bidirectional_unreachable() {
+    let five = Alphabet::new(5);
+    assert_eq!(
+        None,
+        solve_bidirectional(Word::new("a"), Word::new("z"), 1, five)
+    );
+}
+
+#[test]
+fn astar() {
+    let latin = Alphabet::default();
+    assert_eq!(
+        Some(vec![Word::new("a")]),
+        solve_astar(Word::new("a"), Word::new("a"), 1, latin)
+    );
+    for (left, right) in [
+        ("ab", "ca"),
+        ("abc", "bda"),
+        ("abcd", "dcab"),
+        ("cat", "dog"),
+        ("ab", "zzz"),
+        ("hello", "lo"),
+    ] {
+        let (left, right) = (Word::new(left), Word::new(right));
+        let len_limit = left.len().max(right.len());
+        let path = solve_astar(left, right, len_limit, latin).unwrap();
+        let (expected, _) = solve(
+            left,
+            right,
+            len_limit,
+            latin,
+            DEFAULT_MAX_STEPS,
+            DEFAULT_MAX_NODES,
+        )
+        .unwrap()
+        .into_ladder()
+        .unwrap();
+        assert_eq!(expected.len(), path.len(), "{} -> {}", left, right);
+        assert_eq!(Some(&left), path.first());
+        assert_eq!(Some(&right), path.last());
+        for step in path.windows(2) {
+            assert!(step[0]
+                .neighbours_in(len_limit, latin)
+                .any(|w| w == step[1]));
+        }
+    }
+}
+
+#[test]
+fn nearest() {
+    let latin = Alphabet::LATIN;
+    let left = Word::new("abc");
+    let (far, near) = (Word::new("zcad"), Word::new("bda"));
+    let (target, path) = solve_nearest(left, &[far, near], 4, latin).unwrap();
+    assert_eq!(near, target);
+    assert_eq!(Some(path.len() as u32 - 1), distance(left, near, 4, latin));
+    assert_eq!(Ok(()), verify_path(&path, 4, latin));
+    assert_eq!(Some(&left), path.first());
+
+    let (target, path) = solve_nearest(left, &[far], 4, latin).unwrap();
+    assert_eq!(far, target);
+    assert_eq!(6, path.len());
+
+    // The nearer of two at the same distance is the first asked for.
+    let (a, b) = (Word::new("abd"), Word::new("abb"));
+    assert_eq!(a, solve_nearest(left, &[a, b], 3, latin).unwrap().0);
+    assert_eq!(b, solve_nearest(left, &[b, a], 3, latin).unwrap().0);
+
+    assert_eq!(
+        Some((left, vec![left])),
+        solve_nearest(left, &[far, left], 4, latin)
+    );
+    assert_eq!(None, solve_nearest(left, &[], 3, latin));
+    assert_eq!(None, solve_nearest(left, &[far], 3, Alphabet::new(3)));
+}
+
+#[test]
+fn mitm() {
+    let latin = Alphabet::LATIN;
+    for (left, right, len_limit) in [
+        ("abc", "zcad", 4),
+        ("abc", "bda", 3),
+        ("abc", "bca", 3),
+        ("abc", "abc", 3),
+        ("ab", "ba", 2),
+        ("hello", "lo", 5),
+        ("abc", "bzz", 4),
+    ] {
+        let (left, right) = (Word::new(left), Word::new(right));
+        let path = solve_mitm(left, right, len_limit, latin).unwrap();
+        assert_eq!(Ok(()), verify_path(&path, len_limit, latin), "{:?}", path);
+        assert_eq!((Some(&left), Some(&right)), (path.first(), path.last()));
+        let shortest = distance(left, right, len_limit, latin).unwrap();
+        assert_eq!(shortest as usize + 1, path.len(), "{:?}", path);
+    }
+    assert_eq!(
+        None,
+        solve_mitm(Word::new("abc"), Word::new("zcad"), 3, latin)
+    );
+}
+
+#[test]
+fn weighted() {
+    let (left, right) = (Word::new("abc"), Word::new("bca"));
+    let config = SolverConfig::new();
+    let ((path, ops), cost) = solve_weighted(left, right, 3, &config).unwrap();
+    assert_eq!(vec![left, right], path);
+    assert_eq!(vec![Op::RotateLeft], ops);
+    assert_eq!(1, cost);
+
+    // Two cheap moves, e.g. a swap and a reverse, beat one dear rotation.
+    let dear_rotation = config.costs(OpCosts {
+        rotate: 3,
+        ..OpCosts::default()
+    });
+    let ((path, ops), cost) = solve_weighted(left, right, 3, &dear_rotation).unwrap();
+    assert_eq!(2, cost);
+    assert_eq!(3, path.len());
+    assert!(ops
+        .iter()
+        .all(|op| !matches!(op, Op::RotateLeft | Op::RotateRight)));
+    assert_eq!(Ok(()), verify_path(&path, 3, Alphabet::default()));
+
+    // Unit costs agree with the breadth-first search.
+    for (left, right) in [("ab", "ca"), ("cat", "dog")] {
+        let (left, right) = (Word::new(left), Word::new(right));
+        let (_, cost) = solve_weighted(left, right, 3, &config).unwrap();
+        assert_eq!(distance(left, right, 3, Alphabet::default()), Some(cost));
+    }
+
+    let five = config.alphabet(Alphabet::new(5));
+    assert_eq!(
+        None,
+        solve_weighted(Word::new("a"), Word::new("z"), 1, &five)
+    );
+}
+
+#[test]
+fn astar_unreachable() {
+    let five = Alphabet::new(5);
+    assert_eq!(None, solve_astar(Word::new("a"), Word::new("z"), 1, five));
+}
+
+#[test]
+fn mismatch() {
+    assert_eq!(0, letter_mismatch(Word::new("abc"), Word::new("cab")));
+    assert_eq!(1, letter_mismatch(Word::new("abc"), Word::new("abd")));
+    assert_eq!(2, letter_mismatch(Word::new("abc"), Word::new("a")));
+    assert_eq!(3, letter_mismatch(Word::new("ab"), Word::new("zzz")));
+}
+
+#[test]
+fn counts() {
+    let latin = Alphabet::default();
+    let count = |left, right, len_limit| {
+        let (count, saturated) =
+            count_shortest_paths(Word::new(left), Word::new(right), len_limit, latin);
+        assert!(!saturated);
+        count
+    };
+    assert_eq!(1, count("a", "a", 1));
+    // rotate, reverse and swap all give the same word
+    assert_eq!(1, count("ab", "ba", 2));
+    assert_eq!(1, count("abc", "bca", 3));
+    // via bb or ac
+    assert_eq!(2, count("ab", "bc", 2));
+    // thirteen shifts up or thirteen down
+    assert_eq!(2, count("a", "n", 1));
+    assert_eq!(
+        (0, false),
+        count_shortest_paths(Word::new("a"), Word::new("z"), 1, Alphabet::new(5))
+    );
+}
+
+#[test]
+fn reachability() {
+    let (abcd, abcdef) = (Word::new("abcd"), Word::new("abcdef"));
+    let latin = Alphabet::default();
+    assert!(!abcd.is_reachable(&abcdef, 4, latin));
+    assert!(!abcd.is_reachable(&abcdef, 5, latin));
+    assert!(abcd.is_reachable(&abcdef, 6, latin));
+    assert!(abcdef.is_reachable(&abcd, 4, latin));
+    assert!(abcdef.is_reachable(&abcdef, 4, latin));
+    // Already past the limit, but moves that keep the length still work.
+    assert!(abcdef.is_reachable(&Word::new("fedcba"), 4, latin));
+    assert!(!abcd.is_reachable(&Word::new("abcde"), 3, latin));
+    assert!(Word::new("a").is_reachable(&Word::new("z"), 1, latin));
+
+    // Past the end of the alphabet, letters only come down.
+    let five = Alphabet::new(5);
+    let (a, y, z) = (Word::new("a"), Word::new("y"), Word::new("z"));
+    assert!(!a.is_reachable(&z, 1, five));
+    assert!(!y.is_reachable(&z, 1, five));
+    assert!(z.is_reachable(&y, 1, five));
+    assert!(z.is_reachable(&a, 1, five));
+    assert!(abcd.is_reachable(&Word::new("eeee"), 4, five));
+    assert_eq!(None, distance(a, z, 1, five));
+    assert_eq!(Some(1), distance(z, y, 1, five));
+
+    // Rejected without a search, where the search would otherwise wander all
+    // the words up to four letters before giving up.
+    let mut solver = Solver::with_config(SolverConfig::new().len_limit(4));
+    let result = solver.solve(abcd, abcdef).unwrap();
+    assert_eq!(None, result.path);
+    assert_eq!((0, 0), (result.nodes_explored, result.levels));
+    assert!(solver.visited().is_empty());
+
+    let mut solver = Solver::with_config(SolverConfig::new().alphabet(five));
+    let result = solver.solve(a, z).unwrap();
+    assert_eq!(None, result.path);
+    assert_eq!((0, 0), (result.nodes_explored, result.levels));
+
+    let mut solver = Solver::new();
+    solver.filter.forbidden.insert(Word::new("bc"));
+    let result = solver.solve(Word::new("ab"), Word::new("bc")).unwrap();
+    assert_eq!(None, result.path);
+    assert_eq!((0, 0), (result.nodes_explored, result.levels));
+
+    // Everything that is allowed to be reachable is.
+    let two = Alphabet::new(2);
+    let words: Vec<Word> = words_at_distance_in(Word::new("a"), 1, 2, two)
+        .into_iter()
+        .chain(words_at_distance_in(Word::new("a"), 2, 2, two))
+        .chain([Word::new("a")])
+        .collect();
+    assert_eq!(6, words.len());
+    for &from in &words {
+        for &to in &words {
+            assert!(from.is_reachable(&to, 2, two));
+            assert!(distance(from, to, 2, two).is_some(), "{} {}", from, to);
+        }
+    }
+}
+
+#[test]
+fn reconstructs_paths() {
+    let [a, b, c, d] = ["a", "b", "c", "d"].map(Word::new);
+    let mut preds: WordMap<Word> = [(b, a), (c, b), (d, b)].into_iter().collect();
+    assert_eq!(Some(vec![a, b, c]), reconstruct_path(&preds, a, c));
+    assert_eq!(Some(vec![a, b, d]), reconstruct_path(&preds, a, d));
+    assert_eq!(Some(vec![b, c]), reconstruct_path(&preds, b, c));
+    assert_eq!(Some(vec![a]), reconstruct_path(&preds, a, a));
+    assert_eq!(None, reconstruct_path(&preds, a, Word::new("e")));
+    assert_eq!(None, reconstruct_path(&preds, d, c));
+
+    // The start may map to itself, as it does in the searches.
+    preds.insert(a, a);
+    assert_eq!(Some(vec![a, b, c]), reconstruct_path(&preds, a, c));
+
+    // Corrupted: b and a lead to each other, so c never gets back to d.
+    preds.insert(a, b);
+    assert_eq!(None, reconstruct_path(&preds, d, c));
+    let std: HashMap<Word, Word> = [(a, b), (b, a)].into_iter().collect();
+    assert_eq!(None, reconstruct_path(&std, c, a));
+}
+
+#[test]
+fn farthest() {
+    // Within two letters of a and b: a goes to aa and b, they go to ab, ba
+    // and bb, and that's everything.
+    let two = Alphabet::new(2);
+    assert_eq!((Word::new("ab"), 2), farthest_from(Word::new("a"), 2, two));
+    assert_eq!(
+        (Word::new("a"), 0),
+        farthest_from(Word::new("a"), 1, Alphabet::new(1))
+    );
+
+    let latin = Alphabet::default();
+    let (word, depth) = farthest_from(Word::new("a"), 2, latin);
+    assert_eq!(Some(depth), distance(Word::new("a"), word, 2, latin));
+}
+
+#[test]
+fn distance_matrices() {
+    let latin = Alphabet::default();
+    let words: Vec<Word> = ["abc", "bca", "cab", "bac"]
+        .iter()
+        .map(|w| Word::new(w))
+        .collect();
+    let matrix = distance_matrix(&words, 3, latin);
+    assert_eq!(words.len(), matrix.len());
+    for i in 0..words.len() {
+        assert_eq!(Some(0), matrix[i][i]);
+        for j in 0..words.len() {
+            // Rotations, swaps and reversal all have inverses among them.
+            assert_eq!(matrix[i][j], matrix[j][i], "{} {}", words[i], words[j]);
+            assert_eq!(distance(words[i], words[j], 3, latin), matrix[i][j]);
+        }
+    }
+    assert_eq!(Some(1), matrix[0][1]);
+
+    // Not symmetric once a length changes: `pop` is always there, `dupl_first`
+    // only sometimes.
+    let words = [Word::new("ab"), Word::new("b"), Word::new("z")];
+    let five = Alphabet::new(5);
+    let matrix = distance_matrix(&words, 2, five);
+    assert_eq!(Some(1), matrix[0][1]);
+    assert_eq!(Some(2), matrix[1][0]);
+    // Nothing shifts into a letter outside a-e.
+    assert_eq!(None, matrix[0][2]);
+    assert_eq!(None, matrix[1][2]);
+    assert_eq!(Some(0), matrix[2][2]);
+}
+
+#[test]
+fn seeded() {
+    // Through bb or ac, at least.
+    let (left, right) = (Word::new("ab"), Word::new("bc"));
+    let latin = Alphabet::default();
+    assert!(count_shortest_paths(left, right, 2, latin).0 > 1);
+    let ladder = |config: SolverConfig| {
+        Solver::with_config(config)
+            .solve(left, right)
+            .unwrap()
+            .into_ladder()
+            .unwrap()
+            .0
+    };
+    let unseeded = ladder(SolverConfig::new());
+    assert_eq!(unseeded, ladder(SolverConfig::new()));
+    assert_eq!(
+        ladder(SolverConfig::new().seed(7)),
+        ladder(SolverConfig::new().seed(7))
+    );
+    let paths: HashSet<Vec<Word>> = (0..20)
+        .map(|seed| ladder(SolverConfig::new().seed(seed)))
+        .collect();
+    assert!(paths.len() > 1, "{:?}", paths);
+    for path in &paths {
+        assert_eq!(unseeded.len(), path.len(), "{:?}", path);
+        assert_eq!(Ok(()), verify_path(path, 2, latin));
+    }
+}
+
+#[test]
+fn at_distance() {
+    let start = Word::new("abc");
+    assert_eq!(vec![start], words_at_distance(start, 0, 3));
+
+    let one = words_at_distance(start, 1, 3);
+    let mut neighbours: Vec<Word> = start.neighbours(3).filter(|&w| w != start).collect();
+    neighbours.sort();
+    neighbours.dedup();
+    let mut sorted = one.clone();
+    sorted.sort();
+    assert_eq!(neighbours, sorted);
+    assert_eq!(one.len(), sorted.len());
+
+    let latin = Alphabet::default();
+    let two = words_at_distance(start, 2, 3);
+    assert!(!two.is_empty());
+    for word in two.iter().take(20) {
+        assert_eq!(Some(2), distance(start, *word, 3, latin), "{}", word);
+    }
+
+    // Everything within two letters of a and b is two moves from a at most.
+    let ab = Alphabet::new(2);
+    let a = Word::new("a");
+    assert_eq!(2, words_at_distance_in(a, 1, 2, ab).len());
+    assert_eq!(3, words_at_distance_in(a, 2, 2, ab).len());
+    assert!(words_at_distance_in(a, 3, 2, ab).is_empty());
+}
+
+#[test]
+fn distances() {
+    let latin = Alphabet::default();
+    assert_eq!(Some(0), distance(Word::new("a"), Word::new("a"), 1, latin));
+    for (left, right) in [
+        ("ab", "ca"),
+        ("abc", "bda"),
+        ("cat", "dog"),
+        ("hello", "lo"),
+    ] {
+        let (left, right) = (Word::new(left), Word::new(right));
+        let len_limit = left.len().max(right.len());
+        let (path, _) = solve(
+            left,
+            right,
+            len_limit,
+            latin,
+            DEFAULT_MAX_STEPS,
+            DEFAULT_MAX_NODES,
+        )
+        .unwrap()
+        .into_ladder()
+        .unwrap();
+        assert_eq!(
+            Some(path.len() as u32 - 1),
+            distance(left, right, len_limit, latin)
+        );
+    }
+    assert_eq!(
+        None,
+        distance(Word::new("a"), Word::new("z"), 1, Alphabet::new(5))
+    );
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde_round_trips() {
+    let word = Word::new("hello");
+    assert_eq!("\"hello\"", serde_json::to_string(&word).unwrap());
+    assert_eq!(word, serde_json::from_str::<Word>("\"hello\"").unwrap());
+    let words = vec![Word::new("a"), Word::new("abcdefghijkl")];
+    let json = serde_json::to_string(&words).unwrap();
+    assert_eq!(words, serde_json::from_str::<Vec<Word>>(&json).unwrap());
+    let wide = WideWord::new("abcdefghijklmnopqrstuvwxy");
+    let json = serde_json::to_string(&wide).unwrap();
+    assert_eq!(wide, serde_json::from_str::<WideWord>(&json).unwrap());
+
+    let err = serde_json::from_str::<Word>("\"abcdefghijklm\"").unwrap_err();
+    assert!(err.to_string().contains("too long"), "{}", err);
+    let err = serde_json::from_str::<Word>("\"a1\"").unwrap_err();
+    assert!(err.to_string().contains("invalid character"), "{}", err);
+    assert!(serde_json::from_str::<Word>("12").is_err());
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn parallel_expansion() {
+    let latin = Alphabet::default();
+    let words: Vec<Word> = Word::new("hello")
+        .neighbours_in(6, latin)
+        .flat_map(|w| w.neighbours_in(6, latin))
+        .collect();
+    let serial: Vec<(Word, Word, Op)> = words
+        .iter()
+        .flat_map(|&k| moves(k, 6, latin).map(move |(word, op)| (word, k, op)))
+        .collect();
+    assert_eq!(serial, expand_parallel(&words, 6, &SolverConfig::new()));
+
+    let (left, right) = (Word::new("hello"), Word::new("lo"));
+    let (path, _) = solve(left, right, 5, latin, DEFAULT_MAX_STEPS, DEFAULT_MAX_NODES)
+        .unwrap()
+        .into_ladder()
+        .unwrap();
+    assert_eq!(
+        solve_bidirectional(left, right, 5, latin).unwrap().len(),
+        path.len()
+    );
+}
+
+#[test]
+fn step_cap() {
+    let (left, right) = (Word::new("abc"), Word::new("zcad"));
+    let latin = Alphabet::default();
+    assert_eq!(
+        None,
+        solve(left, right, 4, latin, 4, DEFAULT_MAX_NODES)
+            .unwrap()
+            .path
+    );
+    assert_eq!(
+        6,
+        solve(left, right, 4, latin, 5, DEFAULT_MAX_NODES)
+            .unwrap()
+            .into_ladder()
+            .unwrap()
+            .0
+            .len()
+    );
+}
+
+#[test]
+fn node_budget() {
+    let (left, right) = (Word::new("abc"), Word::new("zcad"));
+    assert_eq!(
+        SolveError::Budget { explored: 50 },
+        solve(left, right, 4, Alphabet::default(), DEFAULT_MAX_STEPS, 50).unwrap_err()
+    );
+}
+
+#[test]
+fn verify() {
+    let latin = Alphabet::default();
+    let path = |words: &[&str]| words.iter().map(|w| Word::new(w)).collect::<Vec<_>>();
+    assert_eq!(
+        Ok(()),
+        verify_path(&path(&["abc", "abd", "acd", "aacd"]), 4, latin)
+    );
+    assert_eq!(
+        Err(PathError::Disconnected {
+            index: 1,
+            from: Word::new("abd"),
+            to: Word::new("aacd")
+        }),
+        verify_path(&path(&["abc", "abd", "aacd"]), 4, latin)
+    );
+    // too long to duplicate into
+    assert!(verify_path(&path(&["acd", "aacd"]), 3, latin).is_err());
+    assert_eq!(Ok(()), verify_path(&path(&["abc"]), 3, latin));
+    assert_eq!(Err(PathError::Empty), verify_path(&[], 3, latin));
+}
+
+#[test]
+fn unreachable() {
+    // Shifts wrap within a-e, so nothing ever becomes a z.
+    let five = Alphabet::new(5);
+    let result = solve(
+        Word::new("a"),
+        Word::new("z"),
+        1,
+        five,
+        DEFAULT_MAX_STEPS,
+        DEFAULT_MAX_NODES,
+    )
+    .unwrap();
+    assert_eq!(None, result.path);
+    assert!(result.ops.is_empty());
+    // b and e, then c and d, then nothing new.
+    assert_eq!(4, result.nodes_explored);
+    assert_eq!(3, result.levels);
+}
+
+#[test]
+fn max_letters() {
+    assert_eq!(12, Word::MAX_LETTERS);
+    assert_eq!(25, WideWord::MAX_LETTERS);
+    assert_eq!(u64::BITS / BITS_PER_LETTER, u32::from(Word::MAX_LETTERS));
+    assert_eq!(
+        u128::BITS / BITS_PER_LETTER,
+        u32::from(WideWord::MAX_LETTERS)
+    );
+    assert!(u32::from(LETTER_MASK) >= 26);
+    let full = "z".repeat(Word::MAX_LETTERS.into());
+    assert_eq!(Word::MAX_LETTERS, Word::new(&full).len());
+    assert_eq!(
+        Err(WordError::TooLong {
+            len: full.len() + 1
+        }),
+        Word::try_new(&format!("{}a", full))
+    );
+    assert_eq!(None, Word::new(&full).push(1, u8::MAX));
+}
+
+#[test]
+fn raw_conversions() {
+    for word in ["a", "hello", "zzzzzzzzzzzz"] {
+        let word = Word::new(word);
+        let raw = u64::from(word);
+        assert_eq!(word.as_u64(), raw);
+        assert_eq!(Ok(word), Word::try_from(raw));
+    }
+    let wide = WideWord::new("abcdefghijklmnopqrstuvwxy");
+    assert_eq!(Ok(wide), WideWord::try_from(u128::from(wide)));
+
+    assert_eq!(Err(WordError::NotPacked), Word::try_from(0));
+    // "a", a gap, then "a": the gap would end the word early.
+    assert_eq!(Err(WordError::NotPacked), Word::try_from(1 | 1 << 10));
+    assert_eq!(Err(WordError::NotPacked), Word::try_from(27));
+    assert_eq!(Err(WordError::NotPacked), Word::try_from(1 | 31 << 5));
+    assert_eq!(Err(WordError::NotPacked), Word::try_from(1 << 60));
+}
+
+#[test]
+fn lens() {
+    assert_eq!(1, Word::new("a").len());
+    assert_eq!(1, Word::new("z").len());
+    assert_eq!(2, Word::new("aa").len());
+    assert_eq!(2, Word::new("zz").len());
+    assert_eq!(7, Word::new("aaaaaaa").len());
+    assert_eq!(7, Word::new("zzzzzzz").len());
+    for len in 8..=12 {
+        let a = "a".repeat(len);
+        assert_eq!(len as u8, Word::new(&a).len(), "{}", a);
+        let z = "z".repeat(len);
+        assert_eq!(len as u8, Word::new(&z).len(), "{}", z);
+        // Only the lowest bit of the last letter is set.
+        let top_a = format!("{}a", "z".repeat(len - 1));
+        assert_eq!(len as u8, Word::new(&top_a).len(), "{}", top_a);
+    }
+    assert_eq!(25, WideWord::new(&"a".repeat(25)).len());
+    assert_eq!(25, WideWord::new(&"z".repeat(25)).len());
+}
+
+#[test]
+fn full_words_stay_full() {
+    let full = Word::new("aaaaaaaaaaaa");
+    assert_eq!(None, full.dupl_first(13));
+    assert_eq!(None, full.push(1, 13));
+    assert_eq!(None, full.insert_at(0, 1, 13));
+    assert_eq!(Some(full), Word::new("aaaaaaaaaaa").dupl_first(13));
+    assert_eq!(Some(full), Word::new("aaaaaaaaaaa").push(1, 13));
+    let wide = WideWord::new(&"b".repeat(25));
+    assert_eq!(None, wide.dupl_first(26));
+}
+
+#[test]
+fn strs() {
+    assert_eq!("a", format!("{:?}", Word::new("a")));
+    assert_eq!("ab", format!("{:?}", Word::new("ab")));
+    assert_eq!("abcde", format!("{:?}", Word::new("abcde")));
+    assert_eq!("abcdefghi", format!("{:?}", Word::new("abcdefghi")));
+}
+
+#[test]
+fn try_new_errors() {
+    assert_eq!(Err(WordError::Empty), Word::try_new(""));
+    assert_eq!(Err(WordError::NotAscii), Word::try_new("café"));
+    assert_eq!(
+        Err(WordError::TooLong { len: 13 }),
+        Word::try_new("abcdefghijklm")
+    );
+    assert_eq!(
+        Err(WordError::InvalidChar { ch: '1', index: 1 }),
+        Word::try_new("a1c")
+    );
+    assert_eq!(
+        Err(WordError::InvalidChar { ch: ' ', index: 2 }),
+        Word::try_new("ab cd")
+    );
+    assert_eq!(Ok(Word::new("abc")), Word::try_new("abc"));
+}
+
+#[test]
+fn parses() {
+    assert_eq!(Ok(Word::new("abc")), "abc".parse::<Word>());
+    assert_eq!(Ok(Word::new("hello")), Word::try_from(" Hello"));
+    assert_eq!(Ok(WideWord::new("abc")), "abc".parse::<WideWord>());
+    assert_eq!(Err(WordError::Empty), "".parse::<Word>());
+    assert_eq!(
+        Err(WordError::TooLong { len: 13 }),
+        "abcdefghijklm".parse::<Word>()
+    );
+    assert_eq!(
+        Err(WordError::InvalidChar { ch: '-', index: 2 }),
+        Word::try_from("ab-c")
+    );
+}
+
+#[test]
+fn try_new_normalises() {
+    assert_eq!(Word::new("abc"), Word::new(" AbC "));
+    assert_eq!(Ok(Word::new("hello")), Word::try_new("\tHELLO\n"));
+    assert_eq!(
+        Err(WordError::InvalidChar { ch: ' ', index: 1 }),
+        Word::try_new("a b")
+    );
+    assert_eq!(Err(WordError::Empty), Word::try_new("   "));
+    assert_eq!(Err(WordError::NotAscii), Word::try_new("CAFÉ"));
+}
+
+#[test]
+fn display() {
+    assert_eq!("hello", format!("{}", Word::new("hello")));
+    assert_eq!("hello", format!("{:?}", Word::new("hello")));
+}
+
+#[test]
+fn chars() {
+    assert_eq!("abc", Word::new("abc").chars().collect::<String>());
+    assert_eq!(vec![1, 2, 26], Word::new("abz").codes().collect::<Vec<_>>());
+}
+
+#[test]
+fn ordering() {
+    assert!(Word::new("a") < Word::new("b"));
+    assert!(Word::new("b") < Word::new("aa"));
+    assert!(Word::new("ab") < Word::new("ba"));
+    assert!(Word::new("zz") < Word::new("aaa"));
+
+    let mut words = vec![Word::new("ba"), Word::new("b"), Word::new("ab")];
+    words.sort();
+    assert_eq!(
+        vec![Word::new("b"), Word::new("ab"), Word::new("ba")],
+        words
+    );
+}
+
+#[test]
+fn get_set() {
+    let w = Word::new("abcde");
+    assert_eq!(Some(1), w.get(0));
+    assert_eq!(Some(3), w.get(2));
+    assert_eq!(Some(5), w.get(4));
+    assert_eq!(None, w.get(5));
+
+    assert_eq!(Word::new("zbcde"), w.set(0, 26));
+    assert_eq!(Word::new("abzde"), w.set(2, 26));
+    assert_eq!(Word::new("abcdz"), w.set(4, 26));
+    assert_eq!(5, w.set(4, 1).len());
+}
+
+#[test]
+#[should_panic]
+fn set_invalid_code() {
+    Word::new("abc").set(1, 27);
+}
+
+#[test]
+fn raw_round_trip() {
+    for s in ["a", "z", "abc", "zzzzzzzzzzzz"] {
+        let w = Word::new(s);
+        assert_eq!(Some(w), Word::from_raw_checked(w.as_u64()));
+    }
+    assert_eq!(2 | 1 << 5, Word::new("ba").as_u64());
+}
+
+#[test]
+fn raw_rejects() {
+    assert_eq!(None, Word::from_raw_checked(0));
+    assert_eq!(None, Word::from_raw_checked(1 | 2 << 10));
+    assert_eq!(None, Word::from_raw_checked(27));
+    assert_eq!(
+        None,
+        Word::from_raw_checked(Word::new("a").as_u64() | 1 << 60)
+    );
+}
+
+#[test]
+fn dupl() {
+    assert_eq!(Some(Word::new("aa")), Word::new("a").dupl_first(8));
+    assert_eq!(Some(Word::new("aab")), Word::new("ab").dupl_first(8));
+    assert_eq!(Some(Word::new("aabcde")), Word::new("abcde").dupl_first(8));
+    assert_eq!(None, Word::new("abcdefgh").dupl_first(8));
+}
+
+#[test]
+fn dupl_limit() {
+    assert_eq!(None, Word::new("a").dupl_first(1));
+    assert_eq!(None, Word::new("ab").dupl_first(2));
+}
+
+#[test]
+fn pushy() {
+    assert_eq!(Some(Word::new("ab")), Word::new("a").push(2, 8));
+    assert_eq!(Some(Word::new("abz")), Word::new("ab").push(26, 8));
+    assert_eq!(Some(Word::new("abcdea")), Word::new("abcde").push(1, 8));
+    assert_eq!(None, Word::new("abcdefgh").push(1, 8));
+    assert_eq!(None, Word::new("abcdefghijkl").push(1, 13));
+}
+
+#[test]
+fn push_limit() {
+    assert_eq!(None, Word::new("a").push(1, 1));
+    assert_eq!(None, Word::new("ab").push(1, 2));
+}
+
+#[test]
+fn inserty() {
+    let w = Word::new("abc");
+    assert_eq!(Some(Word::new("zabc")), w.insert_at(0, 26, 8));
+    assert_eq!(Some(Word::new("azbc")), w.insert_at(1, 26, 8));
+    assert_eq!(Some(Word::new("abcz")), w.insert_at(3, 26, 8));
+    assert_eq!(None, w.insert_at(4, 26, 8));
+    assert_eq!(None, w.insert_at(1, 26, 3));
+}
+
+#[test]
+fn removey() {
+    let w = Word::new("abcd");
+    assert_eq!(Some(Word::new("bcd")), w.remove_at(0));
+    assert_eq!(Some(Word::new("acd")), w.remove_at(1));
+    assert_eq!(Some(Word::new("abd")), w.remove_at(2));
+    assert_eq!(Some(Word::new("abc")), w.remove_at(3));
+    assert_eq!(None, w.remove_at(4));
+    assert_eq!(None, Word::new("a").remove_at(0));
+    for index in 0..=4 {
+        assert_eq!(Some(w), w.insert_at(index, 26, 5).unwrap().remove_at(index));
+    }
+    let full = Word::new("abcdefghijkl");
+    assert_eq!(Some(Word::new("abcdefghijk")), full.remove_at(11));
+    assert_eq!(Some(Word::new("bcdefghijkl")), full.remove_at(0));
+}
+
+#[test]
+fn append_words() {
+    let (ab, cd) = (Word::new("ab"), Word::new("cd"));
+    assert_eq!(Some(Word::new("abcd")), ab.append_word(&cd, 4));
+    assert_eq!(Some(Word::new("cdab")), cd.append_word(&ab, 12));
+    assert_eq!(None, ab.append_word(&cd, 3));
+    let eleven = Word::new("abcdefghijk");
+    assert_eq!(
+        Some(Word::new("abcdefghijkz")),
+        eleven.append_word(&Word::new("z"), 12)
+    );
+    assert_eq!(None, eleven.append_word(&ab, 12));
+    assert_eq!(None, eleven.append_word(&ab, u8::MAX));
+    let wide = WideWord::new("abcdefghijklmnopqrstuvwx");
+    assert_eq!(
+        Some(WideWord::new("abcdefghijklmnopqrstuvwxy")),
+        wide.append_word(&WideWord::new("y"), u8::MAX)
+    );
+}
+
+#[test]
+fn slices() {
+    let w = Word::new("abcde");
+    assert_eq!(Some(Word::new("bc")), w.slice(1, 3));
+    assert_eq!(Some(w), w.slice(0, 5));
+    assert_eq!(Some(Word::new("a")), w.slice(0, 1));
+    assert_eq!(Some(Word::new("e")), w.slice(4, 5));
+    assert_eq!(None, w.slice(2, 2));
+    assert_eq!(None, w.slice(3, 1));
+    assert_eq!(None, w.slice(0, 6));
+    assert_eq!(None, w.slice(5, 6));
+    let full = Word::new("abcdefghijkl");
+    assert_eq!(Some(full), full.slice(0, 12));
+    assert_eq!(Some(Word::new("jkl")), full.slice(9, 12));
+    let wide = WideWord::new("abcdefghijklmnopqrstuvwxy");
+    assert_eq!(Some(WideWord::new("xy")), wide.slice(23, 25));
+    let (front, back) = (w.slice(0, 2).unwrap(), w.slice(2, 5).unwrap());
+    assert_eq!(Some(w), front.append_word(&back, 5));
+}
+
+#[test]
+fn removals_configured() {
+    let (left, right) = (Word::new("abcd"), Word::new("abd"));
+    let config = SolverConfig::new().max_nodes(1_000_000);
+    let (path, ops) = Solver::with_config(config)
+        .solve(left, right)
+        .unwrap()
+        .into_ladder()
+        .unwrap();
+    assert!(path.len() > 2);
+    assert!(!ops.iter().any(|op| matches!(op, Op::Remove { .. })));
+
+    let (path, ops) = Solver::with_config(config.allow_remove_at(true))
+        .solve(left, right)
+        .unwrap()
+        .into_ladder()
+        .unwrap();
+    assert_eq!(vec![left, right], path);
+    assert_eq!(vec![Op::Remove { index: 2 }], ops);
+    assert_eq!("abcd --remove:3-> abd", format_ladder(&path, &ops));
+    assert_eq!(Some(right), ops[0].apply(left, 4, Alphabet::LATIN));
+}
+
+#[test]
+fn poppity() {
+    assert_eq!(Some(Word::new("bcde")), Word::new("abcde").pop());
+    assert_eq!(Some(Word::new("b")), Word::new("ab").pop());
+    assert_eq!(None, Word::new("a").pop());
+}
+
+#[test]
+fn pop_lastity() {
+    assert_eq!(Some(Word::new("abcd")), Word::new("abcde").pop_last());
+    assert_eq!(Some(Word::new("a")), Word::new("ab").pop_last());
+    assert_eq!(None, Word::new("a").pop_last());
+}
+
+#[test]
+fn reversal() {
+    assert_eq!(Word::new("a"), Word::new("a").reverse());
+    assert_eq!(Word::new("ba"), Word::new("ab").reverse());
+    assert_eq!(Word::new("edcba"), Word::new("abcde").reverse());
+    assert_eq!(
+        Word::new("lkjihgfedcba"),
+        Word::new("abcdefghijkl").reverse()
+    );
+}
+
+#[test]
+fn swappy() {
+    let mut expected = [None; 11];
+    assert_eq!(expected, Word::new("a").swap_adjacent());
+    expected[0] = Some(Word::new("bac"));
+    expected[1] = Some(Word::new("acb"));
+    assert_eq!(expected, Word::new("abc").swap_adjacent());
+
+    let swaps = Word::new("abcdefghijkl").swap_adjacent();
+    assert_eq!(Some(Word::new("bacdefghijkl")), swaps[0]);
+    assert_eq!(Some(Word::new("abcdefghijlk")), swaps[10]);
+}
+
+#[test]
+fn shifty_edge() {
+    assert_eq!(
+        [
+            Some(Word::new("b")),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(Word::new("z")),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        ],
+        Word::new("a").shifts()
+    );
+    assert_eq!(
+        [
+            Some(Word::new("a")),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(Word::new("y")),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        ],
+        Word::new("z").shifts()
+    );
+}
+
+#[test]
+fn shifty_multiple() {
+    assert_eq!(
+        [
+            Some(Word::new("cc")),
+            Some(Word::new("bd")),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(Word::new("ac")),
+            Some(Word::new("bb")),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        ],
+        Word::new("bc").shifts()
+    );
+}
+
+#[test]
+fn shifty_long() {
+    assert_eq!(
+        [
+            Some(Word::new("pooooo")),
+            Some(Word::new("opoooo")),
+            Some(Word::new("oopooo")),
+            Some(Word::new("ooopoo")),
+            Some(Word::new("oooopo")),
+            Some(Word::new("ooooop")),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+            Some(Word::new("nooooo")),
+            Some(Word::new("onoooo")),
+            Some(Word::new("oonooo")),
+            Some(Word::new("ooonoo")),
+            Some(Word::new("oooono")),
+            Some(Word::new("ooooon")),
+            None,
+            None,
+            None,
+            None,
+            None,
+            None,
+        ],
+        Word::new("oooooo").shifts()
+    );
+}
+
+#[test]
+fn shifty_long_12() {
+    let shifts = Word::new("abcdefghijkl").shifts();
+    assert_eq!(Some(Word::new("abcdefghijkm")), shifts[11]);
+    assert_eq!(Some(Word::new("abcdefghijkk")), shifts[23]);
+}
+
+#[test]
+fn shifty_small_alphabet() {
+    let five = Alphabet::new(5);
+    let shifts = Word::new_in("ea", five).shifts_in(five);
+    assert_eq!(Some(Word::new("aa")), shifts[0]);
+    assert_eq!(Some(Word::new("eb")), shifts[1]);
+    assert_eq!(Some(Word::new("da")), shifts[12]);
+    assert_eq!(Some(Word::new("ee")), shifts[13]);
+}
+
+#[test]
+fn shift_at() {
+    let w = Word::new("azm");
+    assert_eq!(Some(Word::new("bzm")), w.shift_at(0, 1));
+    assert_eq!(Some(Word::new("zzm")), w.shift_at(0, -1));
+    assert_eq!(Some(Word::new("aam")), w.shift_at(1, 1));
+    assert_eq!(Some(Word::new("aym")), w.shift_at(1, -1));
+    assert_eq!(Some(Word::new("azn")), w.shift_at(2, 1));
+    assert_eq!(Some(Word::new("azl")), w.shift_at(2, -1));
+    assert_eq!(Some(w), w.shift_at(2, 26));
+    assert_eq!(Some(Word::new("azl")), w.shift_at(2, -27));
+    assert_eq!(None, w.shift_at(3, 1));
+    assert_eq!(None, w.shift_at(12, -1));
+
+    let shifts = w.shifts();
+    for index in 0..3 {
+        assert_eq!(shifts[usize::from(index)], w.shift_at(index, 1));
+        assert_eq!(shifts[usize::from(index) + 12], w.shift_at(index, -1));
+    }
+    let full = Word::new("zbcdefghijkz");
+    assert_eq!(Some(Word::new("zbcdefghijka")), full.shift_at(11, 1));
+
+    let five = Alphabet::new(5);
+    let w = Word::new("aec");
+    assert_eq!(Some(Word::new("eec")), w.shift_at_in(0, -1, five, true));
+    assert_eq!(None, w.shift_at_in(0, -1, five, false));
+    assert_eq!(Some(Word::new("aac")), w.shift_at_in(1, 1, five, true));
+    assert_eq!(None, w.shift_at_in(1, 1, five, false));
+    assert_eq!(Some(Word::new("aee")), w.shift_at_in(2, 2, five, false));
+    assert_eq!(Some(w), w.shift_at_in(2, 5, five, true));
+    assert_eq!(None, Word::new("z").shift_at_in(0, 1, five, true));
+    let shifts = w.shifts_in(five);
+    for index in 0..3 {
+        assert_eq!(
+            shifts[usize::from(index)],
+            w.shift_at_in(index, 1, five, true)
+        );
+        assert_eq!(
+            shifts[usize::from(index) + 12],
+            w.shift_at_in(index, -1, five, true)
+        );
+    }
+}
+
+#[test]
+fn shifty_packed_full() {
+    for size in [1, 5, 25, 26] {
+        let alphabet = Alphabet::new(size);
+        for word in ["zazazazazaza", "abcdefghijkl", "yyyyyyyyyyyy"] {
+            let word = Word::new(word);
+            assert_eq!(word.shifts_in_scalar(alphabet), word.shifts_in(alphabet));
+        }
+        let wide = WideWord::new("zabcdefghijklmnopqrstuvwy");
+        assert_eq!(wide.shifts_in_scalar(alphabet), wide.shifts_in(alphabet));
+    }
+}
+
+#[test]
+fn small_alphabet_words() {
+    let five = Alphabet::new(5);
+    assert_eq!(Ok(Word::new("abcde")), Word::try_new_in("abcde", five));
+    assert_eq!(
+        Err(WordError::InvalidChar { ch: 'f', index: 1 }),
+        Word::try_new_in("afc", five)
+    );
+}
+
+#[test]
+fn rotter() {
+    assert_eq!([None, None], Word::new("a").rotate());
+    assert_eq!(
+        [Some(Word::new("aa")), Some(Word::new("aa"))],
+        Word::new("aa").rotate()
+    );
+    assert_eq!(
+        [Some(Word::new("ba")), Some(Word::new("ba"))],
+        Word::new("ab").rotate()
+    );
+    assert_eq!(
+        [Some(Word::new("bca")), Some(Word::new("cab"))],
+        Word::new("abc").rotate()
+    );
+}
+
+#[test]
+fn rotate_full_words() {
+    let w = Word::new("abcdefghijkl");
+    let [left, right] = w.rotate();
+    assert_eq!(Some(Word::new("bcdefghijkla")), left);
+    assert_eq!(Some(Word::new("labcdefghijk")), right);
+    for rotated in w.rotate().into_iter().flatten() {
+        assert_eq!(Some(rotated), Word::from_raw_checked(rotated.as_u64()));
+    }
+
+    let w = WideWord::new("abcdefghijklmnopqrstuvwxy");
+    let [left, right] = w.rotate();
+    assert_eq!(Some(WideWord::new("bcdefghijklmnopqrstuvwxya")), left);
+    assert_eq!(Some(WideWord::new("yabcdefghijklmnopqrstuvwx")), right);
+}
+
+#[test]
+fn rotations_undo_each_other() {
+    for s in ["ab", "abc", "zzaz", "hello", "abcdefghijkl", "zyxwvutsrqpo"] {
+        let w = Word::new(s);
+        let [left, right] = w.rotate();
+        assert_eq!(Some(w), left.unwrap().rotate()[1], "{}", s);
+        assert_eq!(Some(w), right.unwrap().rotate()[0], "{}", s);
+    }
+    let w = WideWord::new("zyxwvutsrqponmlkjihgfedcb");
+    let [left, right] = w.rotate();
+    assert_eq!(Some(w), left.unwrap().rotate()[1]);
+    assert_eq!(Some(w), right.unwrap().rotate()[0]);
+}
+
+#[test]
+fn rotate_by() {
+    let w = Word::new("abcd");
+    assert_eq!(Word::new("bcda"), w.rotate_by(1));
+    assert_eq!(Word::new("cdab"), w.rotate_by(2));
+    assert_eq!(Word::new("dabc"), w.rotate_by(-1));
+    assert_eq!(Word::new("cdab"), w.rotate_by(-2));
+    assert_eq!(w, w.rotate_by(4));
+    assert_eq!(w, w.rotate_by(-4));
+    assert_eq!(w, w.rotate_by(0));
+    assert_eq!(Word::new("bcda"), w.rotate_by(5));
+    assert_eq!(Word::new("a"), Word::new("a").rotate_by(3));
+
+    for s in ["ab", "abc", "hello", "abcdefghijkl"] {
+        let w = Word::new(s);
+        assert_eq!(
+            w.rotate(),
+            [Some(w.rotate_by(1)), Some(w.rotate_by(-1))],
+            "{}",
+            s
+        );
+        assert_eq!(w, w.rotate_by(i8::MAX).rotate_by(-i8::MAX), "{}", s);
+    }
+    let w = WideWord::new("abcdefghijklmnopqrstuvwxy");
+    assert_eq!(w.rotate(), [Some(w.rotate_by(1)), Some(w.rotate_by(-1))]);
+    assert_eq!(WideWord::new("xyabcdefghijklmnopqrstuvw"), w.rotate_by(-2));
+    assert_eq!(w, w.rotate_by(25));
+}
+
+#[test]
+fn hamming_distances() {
+    let w = Word::new;
+    assert_eq!(Some(0), w("abc").hamming(&w("abc")));
+    assert_eq!(Some(1), w("abc").hamming(&w("abd")));
+    assert_eq!(Some(3), w("abc").hamming(&w("bca")));
+    assert_eq!(Some(12), w("abcdefghijkl").hamming(&w("zzzzzzzzzzzz")));
+    assert_eq!(None, w("abc").hamming(&w("ab")));
+    assert_eq!(
+        Some(2),
+        WideWord::new("abcdefghijklmnopqrstuvwxy")
+            .hamming(&WideWord::new("abcdefghijklmnopqrstuvwza"))
+    );
+}
+
+#[test]
+fn letters() {
+    let w = Word::new("aabbc");
+    let mut expected = [0; 26];
+    expected[..3].copy_from_slice(&[2, 2, 1]);
+    assert_eq!(expected, w.letter_counts());
+    assert!(w.contains_letter(1));
+    assert!(w.contains_letter(3));
+    assert!(!w.contains_letter(4));
+    assert!(!w.contains_letter(26));
+    assert!(!w.contains_letter(0));
+
+    let z = WideWord::new(&"z".repeat(25));
+    assert_eq!(25, z.letter_counts()[25]);
+    assert_eq!(
+        25,
+        z.letter_counts().iter().map(|&n| u32::from(n)).sum::<u32>()
+    );
+}
+
+#[test]
+fn common_ends() {
+    let w = Word::new;
+    assert_eq!(3, w("abc").common_prefix_len(&w("abc")));
+    assert_eq!(3, w("abc").common_suffix_len(&w("abc")));
+    assert_eq!(0, w("abc").common_prefix_len(&w("xyz")));
+    assert_eq!(0, w("abc").common_suffix_len(&w("xyz")));
+    assert_eq!(2, w("abcd").common_prefix_len(&w("abzd")));
+    assert_eq!(1, w("abcd").common_suffix_len(&w("abzd")));
+    assert_eq!(2, w("ab").common_prefix_len(&w("abcde")));
+    assert_eq!(0, w("ab").common_suffix_len(&w("abcde")));
+    assert_eq!(2, w("hello").common_suffix_len(&w("lo")));
+    assert_eq!(0, w("hello").common_prefix_len(&w("lo")));
+    let full = w("abcdefghijkl");
+    assert_eq!(12, full.common_prefix_len(&full));
+    assert_eq!(11, full.common_suffix_len(&w("zbcdefghijkl")));
+    let wide = WideWord::new("abcdefghijklmnopqrstuvwxy");
+    assert_eq!(25, wide.common_suffix_len(&wide));
+}
+
+#[test]
+fn length_diffs() {
+    let w = Word::new;
+    assert_eq!(0, w("abc").length_diff(&w("xyz")));
+    assert_eq!(2, w("abc").length_diff(&w("a")));
+    assert_eq!(2, w("a").length_diff(&w("abc")));
+    assert_eq!(11, w("a").length_diff(&w("abcdefghijkl")));
+    for (left, right) in [("ab", "ca"), ("hello", "lo"), ("abc", "zcad")] {
+        let (left, right) = (w(left), w(right));
+        let d = distance(left, right, 5, Alphabet::default()).unwrap();
+        assert!(left.length_diff(&right) <= d);
+    }
+}
+
+#[test]
+fn palindromes() {
+    assert!(Word::new("aba").is_palindrome());
+    assert!(Word::new("a").is_palindrome());
+    assert!(Word::new("abba").is_palindrome());
+    assert!(!Word::new("abc").is_palindrome());
+    assert!(!Word::new("ab").is_palindrome());
+    assert!(Word::new("abcdefedcba").is_palindrome());
+    assert!(WideWord::new("abcdefghijklmlkjihgfedcba").is_palindrome());
+    for s in ["aba", "abc", "abba", "abcab"] {
+        let w = Word::new(s);
+        assert_eq!(w.is_palindrome(), w == w.reverse(), "{}", s);
+    }
+}
+
+#[test]
+fn wide_strs() {
+    let w = WideWord::new("abcdefghijklmnopqrst");
+    assert_eq!(20, w.len());
+    assert_eq!("abcdefghijklmnopqrst", format!("{:?}", w));
+    assert_eq!(
+        Ok(WideWord::new("abcdefghijklmnopqrstuvwxy")),
+        WideWord::try_new("abcdefghijklmnopqrstuvwxy")
+    );
+    assert_eq!(
+        Err(WordError::TooLong { len: 26 }),
+        WideWord::try_new("abcdefghijklmnopqrstuvwxyz")
+    );
+}
+
+#[test]
+fn wide_ops() {
+    let w = WideWord::new("abcdefghijklmnopqrst");
+    assert_eq!(
+        Some(WideWord::new("aabcdefghijklmnopqrst")),
+        w.dupl_first(25)
+    );
+    assert_eq!(None, w.dupl_first(20));
+    assert_eq!(Some(WideWord::new("bcdefghijklmnopqrst")), w.pop());
+    assert_eq!(
+        [
+            Some(WideWord::new("bcdefghijklmnopqrsta")),
+            Some(WideWord::new("tabcdefghijklmnopqrs"))
+        ],
+        w.rotate()
+    );
+
+    let shifts = w.shifts();
+    assert_eq!(Some(WideWord::new("bbcdefghijklmnopqrst")), shifts[0]);
+    assert_eq!(Some(WideWord::new("abcdefghijklmnopqrsu")), shifts[19]);
+    assert_eq!(None, shifts[20]);
+    assert_eq!(Some(WideWord::new("zbcdefghijklmnopqrst")), shifts[25]);
+    assert_eq!(Some(WideWord::new("abcdefghijklmnopqrss")), shifts[44]);
+    assert_eq!(None, shifts[45]);
+}
@@ -1,351 +1,1538 @@
-use std::collections::hash_map::Entry;
-use std::collections::HashMap;
-use std::io::Write;
-use std::num::NonZeroU64;
-use std::time::Instant;
-use std::{fmt, fs};
-
-#[derive(Copy, Clone, Eq, PartialEq, Hash)]
-struct Word(NonZeroU64);
-
-impl fmt::Debug for Word {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let mut w = self.0.get();
-        while w != 0 {
-            let masked = (w & 31) as u8;
-            write!(f, "{}", (masked + b'a' - 1) as char)?;
-            w >>= 5;
-        }
-        Ok(())
-    }
-}
-
-impl Word {
-    fn new(from: &str) -> Self {
-        assert!(!from.is_empty());
-        assert!(from.is_ascii());
-        if from.len() > (u64::BITS / 5) as usize {
-            panic!("TOO LONG: {:?}", from);
-        }
-        let mut w: u64 = 0;
-        for (idx, c) in from.chars().enumerate() {
-            if c < 'a' || c > 'z' {
-                panic!("invalid character: {:?}", c);
+use std::collections::{BTreeMap, HashSet};
+use std::io::{self, BufRead, BufReader, BufWriter, IsTerminal, Write};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use std::{env, fs, process};
+
+use uwcsmini::{
+    format_ladder, format_reversed_ladder, moves, Alphabet, Ladder, LadderCache, LevelStat,
+    SolveError, SolveResult, Solver, SolverConfig, Word, WordError, WordSet,
+};
+
+#[cfg(test)]
+use uwcsmini::{Op, DEFAULT_MAX_STEPS};
+
+/// How results are written to stdout.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+enum Format {
+    #[default]
+    Text,
+    /// One JSON object per pair.
+    Json,
+    /// A header row, then one row per pair.
+    Csv,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(format!("unknown format {:?}", s)),
+        }
+    }
+}
+
+/// What to do with a pair that has letters outside `a`-`z`.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+enum AsciiMode {
+    /// Report the line and move on.
+    #[default]
+    Skip,
+    /// Drop accents first, so `café` becomes `cafe`; skip anything left over.
+    Transliterate,
+}
+
+impl FromStr for AsciiMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "skip" => Ok(AsciiMode::Skip),
+            "transliterate" => Ok(AsciiMode::Transliterate),
+            _ => Err(format!("unknown --ascii-only mode {:?}", s)),
+        }
+    }
+}
+
+/// How each pair's result and progress are written, whatever ran it.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+struct Output {
+    format: Format,
+    /// Only the results: no "trying" or per-level lines on stderr.
+    quiet: bool,
+    /// Write each ladder from the second word back to the first.
+    reverse_output: bool,
+    /// Only print every this many levels' stats; every level without one.
+    progress_interval: Option<u32>,
+}
+
+#[derive(Debug, Default, Eq, PartialEq)]
+struct Args {
+    /// A file of pairs, or `-` for stdin.
+    input: Option<String>,
+    output: Output,
+    /// Overrides `UWCSMINI_LOG` and the default `log.log`.
+    log: Option<String>,
+    /// Where to write each ladder found to a file of its own.
+    out_dir: Option<String>,
+    /// Solve everything afresh, without reading or writing `cache.txt`.
+    no_cache: bool,
+    /// Hold every result back until all are solved, then print them shortest
+    /// ladder first.
+    sort_by_steps: bool,
+    /// A word list every step between the endpoints has to come from.
+    dict: Option<String>,
+    /// Where to write the tree explored for the last pair, as Graphviz DOT.
+    dot: Option<String>,
+    /// Keep the DOT output to words this many moves off the ladder.
+    dot_near: Option<u32>,
+    /// How long words along a ladder may get, rather than the longer end.
+    max_len: Option<u8>,
+    /// Only parse the input and report what's wrong with it; solve nothing.
+    check: bool,
+    /// Give up on input with any bad lines, rather than skipping them.
+    strict: bool,
+    /// Abandon any pair still unsolved after this long, and move on.
+    timeout: Option<Duration>,
+    /// Pick between equally short ladders at random, from this seed.
+    seed: Option<u64>,
+    /// Search from one queue, for less memory and no per-level progress.
+    queue: bool,
+    ascii_only: AsciiMode,
+    /// Solve on this many threads, each with its own map.
+    jobs: Option<usize>,
+    /// Only solve this many pairs: the first, once repeats are dropped and
+    /// the shortest words moved to the front.
+    limit: Option<usize>,
+    /// Don't let shifts go round from `z` to `a` or back.
+    no_wrap: bool,
+}
+
+const NEIGHBOURS_USAGE: &str = "usage: uwcsmini neighbours WORD [LEN_LIMIT]";
+
+const REPL_USAGE: &str = "usage: uwcsmini repl";
+
+const SOLVE_USAGE: &str = "usage: uwcsmini solve [--raw] LEFT RIGHT";
+
+const USAGE: &str =
+    "usage: uwcsmini [--format text|json|csv] [--log PATH] [--quiet] [--out-dir DIR] [--sort-by-steps] [--no-cache] [--dict FILE] [--dot FILE [--dot-near N]] [--max-len N] [--check] [--strict] [--reverse-output] [--timeout-ms N] [--seed N] [--queue] [--ascii-only skip|transliterate] [--progress-interval N] [--jobs N] [--limit N] [--no-wrap] [FILE|-]";
+
+fn parse_args(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args::default();
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--format" => {
+                let value = args.next().ok_or("--format needs a value")?;
+                parsed.output.format = value.parse()?;
+            }
+            "--ascii-only" => {
+                let value = args.next().ok_or("--ascii-only needs a mode")?;
+                parsed.ascii_only = value.parse()?;
+            }
+            "--log" => parsed.log = Some(args.next().ok_or("--log needs a path")?),
+            "--quiet" | "-q" => parsed.output.quiet = true,
+            "--sort-by-steps" => parsed.sort_by_steps = true,
+            "--no-cache" => parsed.no_cache = true,
+            "--check" => parsed.check = true,
+            "--strict" => parsed.strict = true,
+            "--reverse-output" => parsed.output.reverse_output = true,
+            "--queue" => parsed.queue = true,
+            "--no-wrap" => parsed.no_wrap = true,
+            "--timeout-ms" => {
+                let value = args.next().ok_or("--timeout-ms needs a number")?;
+                let ms = value
+                    .parse()
+                    .map_err(|_| format!("bad --timeout-ms {:?}", value))?;
+                parsed.timeout = Some(Duration::from_millis(ms));
             }
-            w |= (((c as u8) - b'a' + 1) as u64) << (idx * 5);
+            "--progress-interval" => {
+                let value = args.next().ok_or("--progress-interval needs a number")?;
+                let interval = value
+                    .parse()
+                    .ok()
+                    .filter(|&interval| interval > 0)
+                    .ok_or_else(|| format!("bad --progress-interval {:?}", value))?;
+                parsed.output.progress_interval = Some(interval);
+            }
+            "--limit" => {
+                let value = args.next().ok_or("--limit needs a number")?;
+                let limit = value
+                    .parse()
+                    .map_err(|_| format!("bad --limit {:?}", value))?;
+                parsed.limit = Some(limit);
+            }
+            "--jobs" => {
+                let value = args.next().ok_or("--jobs needs a number")?;
+                let jobs = value
+                    .parse()
+                    .ok()
+                    .filter(|&jobs| jobs > 0)
+                    .ok_or_else(|| format!("bad --jobs {:?}", value))?;
+                if !cfg!(feature = "parallel") {
+                    return Err("--jobs needs the parallel feature".to_string());
+                }
+                parsed.jobs = Some(jobs);
+            }
+            "--seed" => {
+                let value = args.next().ok_or("--seed needs a number")?;
+                let seed = value
+                    .parse()
+                    .map_err(|_| format!("bad --seed {:?}", value))?;
+                parsed.seed = Some(seed);
+            }
+            "--out-dir" => parsed.out_dir = Some(args.next().ok_or("--out-dir needs a directory")?),
+            "--dict" => parsed.dict = Some(args.next().ok_or("--dict needs a path")?),
+            "--dot" => parsed.dot = Some(args.next().ok_or("--dot needs a path")?),
+            "--dot-near" => {
+                let value = args.next().ok_or("--dot-near needs a number")?;
+                let near = value
+                    .parse()
+                    .map_err(|_| format!("bad --dot-near {:?}", value))?;
+                parsed.dot_near = Some(near);
+            }
+            "--max-len" => {
+                let value = args.next().ok_or("--max-len needs a number")?;
+                let max_len = value
+                    .parse()
+                    .ok()
+                    .filter(|len| (1..=Word::MAX_LETTERS).contains(len))
+                    .ok_or_else(|| {
+                        format!("--max-len {:?} isn't 1 to {}", value, Word::MAX_LETTERS)
+                    })?;
+                parsed.max_len = Some(max_len);
+            }
+            _ if arg.starts_with("--") => return Err(format!("unknown option {}", arg)),
+            _ => {
+                if parsed.input.replace(arg).is_some() {
+                    return Err("only one input file, please".to_string());
+                }
+            }
+        }
+    }
+    if parsed.jobs.is_some() && parsed.dot.is_some() {
+        return Err("--dot only works on one thread, without --jobs".to_string());
+    }
+    Ok(parsed)
+}
+
+/// Every word one move from `WORD`, a line per kind of move, for poking at
+/// the moves without solving anything.
+fn neighbours_command(args: &[String]) -> Result<String, String> {
+    let (word, len_limit) = match args {
+        [word] => (word, None),
+        [word, len_limit] => (word, Some(len_limit)),
+        _ => return Err("expected a word, and maybe a length limit".to_string()),
+    };
+    let word = Word::try_new(word).map_err(|e| format!("{}: {}", word, e))?;
+    let len_limit = match len_limit {
+        Some(value) => value
+            .parse()
+            .ok()
+            .filter(|len| (1..=Word::MAX_LETTERS).contains(len))
+            .ok_or_else(|| format!("length limit {:?} isn't 1 to {}", value, Word::MAX_LETTERS))?,
+        None => word.len(),
+    };
+
+    let mut groups: Vec<(String, Vec<Word>)> = Vec::new();
+    for (next, op) in moves(word, len_limit, Alphabet::LATIN) {
+        if next == word {
+            continue;
+        }
+        let op = op.to_string();
+        let kind = op.split(':').next().unwrap_or(&op);
+        match groups.iter_mut().find(|(k, _)| k == kind) {
+            Some((_, words)) => words.push(next),
+            None => groups.push((kind.to_string(), vec![next])),
+        }
+    }
+    let mut out = String::new();
+    for (kind, mut words) in groups {
+        words.sort();
+        words.dedup();
+        let words: Vec<String> = words.iter().map(|w| w.to_string()).collect();
+        out.push_str(&format!("{}: {}\n", kind, words.join(" ")));
+    }
+    Ok(out)
+}
+
+/// The pair for `solve`: words, or with `--raw`, the integers `Word` packs
+/// them into.
+fn solve_args(args: &[String]) -> Result<(Word, Word), String> {
+    let (raw, words) = match args {
+        [flag, words @ ..] if flag == "--raw" => (true, words),
+        words => (false, words),
+    };
+    let [left, right] = words else {
+        return Err(format!("expected two words, found {}", words.len()));
+    };
+    let word = |from: &String| {
+        let word = if raw {
+            let val: u64 = from
+                .parse()
+                .map_err(|_| format!("{}: not a number", from))?;
+            Word::try_from(val)
+        } else {
+            Word::try_new(from)
+        };
+        word.map_err(|e| format!("{}: {}", from, e))
+    };
+    Ok((word(left)?, word(right)?))
+}
+
+fn main() {
+    let argv: Vec<String> = env::args().skip(1).collect();
+    match argv.first().map(String::as_str) {
+        Some("neighbours") => {
+            match neighbours_command(&argv[1..]) {
+                Ok(out) => print!("{}", out),
+                Err(e) => {
+                    eprintln!("{}\n{}", e, NEIGHBOURS_USAGE);
+                    process::exit(2);
+                }
+            }
+            return;
+        }
+        Some("solve") => {
+            let (left, right) = match solve_args(&argv[1..]) {
+                Ok(pair) => pair,
+                Err(e) => {
+                    eprintln!("{}\n{}", e, SOLVE_USAGE);
+                    process::exit(2);
+                }
+            };
+            let mut solver = Solver::new();
+            let output = Output {
+                quiet: true,
+                ..Output::default()
+            };
+            let result = solve_pair(&mut solver, left, right, &output);
+            println!("{}", result.text());
+            return;
         }
-        Word::raw(w)
+        Some("repl") => {
+            if argv.len() > 1 {
+                eprintln!("{}", REPL_USAGE);
+                process::exit(2);
+            }
+            let stdin = io::stdin();
+            let prompt = stdin.is_terminal();
+            if let Err(e) = repl(stdin.lock(), &mut io::stdout(), &mut Solver::new(), prompt) {
+                eprintln!("can't talk: {}", e);
+                process::exit(1);
+            }
+            return;
+        }
+        _ => {}
+    }
+    let args = match parse_args(argv) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("{}\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+    let arg = args.input.as_deref();
+    let pairs = match arg {
+        Some("-") => parse_pairs(io::stdin().lock()),
+        None if !io::stdin().is_terminal() => parse_pairs(io::stdin().lock()),
+        _ => {
+            let path = arg.unwrap_or("input.txt");
+            match open_input(path) {
+                Ok(file) => parse_pairs(file),
+                Err(e) => {
+                    eprintln!("can't read {}: {}", path, e);
+                    process::exit(1);
+                }
+            }
+        }
+    };
+    let (pairs, mut rejects) = match pairs {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("can't read input: {}", e);
+            process::exit(1);
+        }
+    };
+
+    let (inputs, bad_words) = to_words(pairs, args.ascii_only);
+    rejects.extend(bad_words);
+    rejects.sort();
+    for (line, reject) in &rejects {
+        eprintln!("line {}: {}", line, reject);
+    }
+    if args.strict && !rejects.is_empty() {
+        eprintln!("giving up on {} bad lines", rejects.len());
+        process::exit(1);
+    }
+    if args.check {
+        println!("{} valid pairs", inputs.len());
+        process::exit(if rejects.is_empty() { 0 } else { 1 });
     }
 
-    fn raw(val: u64) -> Self {
-        Word(NonZeroU64::new(val).unwrap())
+    let (mut inputs, duplicates) = dedup_pairs(inputs);
+    if duplicates > 0 {
+        eprintln!("skipping {} duplicate pairs", duplicates);
     }
 
-    fn len(&self) -> u8 {
-        let w = self.0.get();
-        let first_bit_set = u64::BITS - w.leading_zeros() + (5 - 1);
-        (first_bit_set / 5) as u8
+    inputs.sort_by_key(|(left, right)| left.len().max(right.len()));
+    if let Some(limit) = args.limit {
+        inputs.truncate(limit);
     }
 
-    fn dupl_first(&self, len_limit: u8) -> Option<Self> {
-        if self.len() >= len_limit {
-            return None;
+    let log_path = args
+        .log
+        .clone()
+        .or_else(|| env::var("UWCSMINI_LOG").ok())
+        .unwrap_or_else(|| DEFAULT_LOG_PATH.to_string());
+    let mut log = match Log::open(&log_path) {
+        Ok(log) => log,
+        Err(e) => {
+            eprintln!("can't open log {}: {}", log_path, e);
+            process::exit(1);
         }
-        let mut w = self.0.get();
-        let s = w & 31;
-        w <<= 5;
-        w |= s;
-        Some(Word::raw(w))
+    };
+
+    if args.output.format == Format::Csv {
+        println!("{}", CSV_HEADER);
     }
 
-    fn pop(&self) -> Option<Self> {
-        let mut w = self.0.get();
-        w >>= 5;
-        if w == 0 {
-            return None;
+    let mut config = SolverConfig::new();
+    if let Some(max_len) = args.max_len {
+        config = config.len_limit(max_len);
+    }
+    if let Some(timeout) = args.timeout {
+        config = config.timeout(timeout);
+    }
+    if let Some(seed) = args.seed {
+        config = config.seed(seed);
+    }
+    config = config.use_queue(args.queue).allow_wrap(!args.no_wrap);
+    let mut solver = Solver::with_config(config);
+    if !args.no_cache {
+        match LadderCache::load(CACHE_PATH) {
+            Ok(cache) => solver.cache = Some(cache),
+            Err(e) => eprintln!("can't read {}, not caching: {}", CACHE_PATH, e),
+        }
+    }
+    if let Some(path) = &args.dict {
+        let dictionary = match fs::File::open(path) {
+            Ok(file) => parse_dictionary(BufReader::new(file)),
+            Err(e) => Err(e),
+        };
+        match dictionary {
+            Ok((words, skipped)) => {
+                for (word, e) in skipped {
+                    eprintln!("skipping dictionary word {}: {}", word, e);
+                }
+                solver.filter.dictionary = Some(words);
+            }
+            Err(e) => {
+                eprintln!("can't read {}: {}", path, e);
+                process::exit(1);
+            }
+        }
+    }
+    let mut lengths = BTreeMap::new();
+    if args.sort_by_steps || args.jobs.is_some() {
+        let mut results: Vec<PairResult> = match args.jobs {
+            #[cfg(feature = "parallel")]
+            Some(jobs) => solve_in_parallel(&mut solver, inputs, jobs, &args),
+            _ => inputs
+                .into_iter()
+                .map(|(left, right)| solve_pair(&mut solver, left, right, &args.output))
+                .collect(),
+        };
+        if args.sort_by_steps {
+            sort_by_steps(&mut results);
+        }
+        for result in &results {
+            print_result(result, &mut log, &args);
+            tally(&mut lengths, result);
+        }
+    } else {
+        for (left, right) in inputs {
+            let result = solve_pair(&mut solver, left, right, &args.output);
+            print_result(&result, &mut log, &args);
+            tally(&mut lengths, &result);
         }
-        Some(Word::raw(w))
     }
 
-    fn rotate(&self) -> [Option<Self>; 2] {
-        let mask = 31;
+    if !args.output.quiet {
+        eprint!("{}", histogram(&lengths));
+        let totals = solver.totals;
+        eprintln!(
+            "{} pairs: {} nodes explored, peak frontier {}, {:?} solving",
+            totals.pairs, totals.nodes_explored, totals.peak_frontier, totals.elapsed
+        );
+    }
 
-        let len = self.len();
+    if let Some(path) = &args.dot {
+        if let Err(e) = fs::write(path, solver.dot(args.dot_near)) {
+            eprintln!("can't write {}: {}", path, e);
+        }
+    }
 
-        if 1 == len {
-            return [None, None];
+    if let Some(cache) = &solver.cache {
+        if let Err(e) = cache.save(CACHE_PATH) {
+            eprintln!("can't write {}: {}", CACHE_PATH, e);
         }
+    }
+}
 
-        let w = self.0.get();
+/// Ladders from earlier runs, unless `--no-cache`.
+const CACHE_PATH: &str = "cache.txt";
 
-        let last = (len - 1) * 5;
+/// Drops all but the first of each repeated pair, returning what's left in
+/// order and how many went. A pair and its reverse are both kept: `pop` has no
+/// general inverse, so the ladders can differ.
+fn dedup_pairs(pairs: Vec<(Word, Word)>) -> (Vec<(Word, Word)>, usize) {
+    let before = pairs.len();
+    let mut seen = HashSet::new();
+    let kept: Vec<(Word, Word)> = pairs
+        .into_iter()
+        .filter(|&pair| seen.insert(pair))
+        .collect();
+    let skipped = before - kept.len();
+    (kept, skipped)
+}
 
-        let start = w & mask;
-        let end = (w & (mask << last)) >> last;
+/// A pair as written, after the line it came from, counting from 1.
+type RawPair = (usize, String, String);
 
-        let right = w >> 5;
-        let left = (w << 5) & !(mask << len * 5);
+/// The line a pair was on, and why it was skipped.
+type Reject = (usize, String);
 
-        return [
-            Some(Word::raw(right | (start << last))),
-            Some(Word::raw(left | end)),
-        ];
+/// `path`, decompressed on the way if it ends `.gz`.
+fn open_input(path: &str) -> io::Result<Box<dyn BufRead>> {
+    let file = fs::File::open(path)?;
+    if !path.ends_with(".gz") {
+        return Ok(Box::new(BufReader::new(file)));
     }
+    #[cfg(feature = "gzip")]
+    return Ok(Box::new(BufReader::new(flate2::read::GzDecoder::new(file))));
+    #[cfg(not(feature = "gzip"))]
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "built without the gzip feature",
+    ))
+}
 
-    fn shifts(&self) -> [Option<Self>; 12] {
-        let us = self.0.get();
-        let mut ret: [Option<Self>; 12] = Default::default();
-        for i in 0..6 {
-            let shift = i * 5;
-            let mask = 31 << shift;
-            let c = ((us & mask) >> shift) as u8;
-            if c == 0 {
-                break;
+/// Blank lines, and comments starting `#`, are skipped. Lines without
+/// exactly two words are rejected.
+fn parse_pairs(input: impl BufRead) -> io::Result<(Vec<RawPair>, Vec<Reject>)> {
+    let mut pairs = Vec::new();
+    let mut rejects = Vec::new();
+    // Split on bytes, not `lines`, so one line that isn't UTF-8 is just one
+    // bad line rather than the end of the input.
+    for (number, line) in input.split(b'\n').enumerate() {
+        let mut line = line?;
+        if line.last() == Some(&b'\r') {
+            line.pop();
+        }
+        let line = match String::from_utf8(line) {
+            Ok(line) => line,
+            Err(e) => {
+                let bytes = e.as_bytes().escape_ascii();
+                rejects.push((number + 1, format!("not utf-8: {}", bytes)));
+                continue;
             }
-            let w = us & !mask;
-            let mut up = c + 1;
-            let mut down = c - 1;
-            if up == 27 {
-                up = 1;
+        };
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line.split_whitespace().collect::<Vec<_>>()[..] {
+            [left, right] => pairs.push((number + 1, left.to_string(), right.to_string())),
+            ref words => rejects.push((
+                number + 1,
+                format!("expected two words, found {}", words.len()),
+            )),
+        }
+    }
+    Ok((pairs, rejects))
+}
+
+/// The pairs that make valid words, and the line and reason for each that
+/// doesn't.
+fn to_words(pairs: Vec<RawPair>, ascii: AsciiMode) -> (Vec<(Word, Word)>, Vec<Reject>) {
+    let mut words = Vec::new();
+    let mut rejects = Vec::new();
+    let word = |from: &str| match ascii {
+        AsciiMode::Skip => Word::try_new(from),
+        AsciiMode::Transliterate => Word::try_new(&transliterate(from)),
+    };
+    for (line, left, right) in pairs {
+        match (word(&left), word(&right)) {
+            (Ok(left), Ok(right)) => words.push((left, right)),
+            (Err(WordError::NotAscii), _) | (_, Err(WordError::NotAscii)) => {
+                let bad = if left.is_ascii() { &right } else { &left };
+                rejects.push((
+                    line,
+                    format!(
+                        "skipping {} {}: not ascii: {}",
+                        left,
+                        right,
+                        bad.as_bytes().escape_ascii()
+                    ),
+                ))
             }
+            (Err(e), _) | (_, Err(e)) => {
+                rejects.push((line, format!("skipping {} {}: {}", left, right, e)))
+            }
+        }
+    }
+    (words, rejects)
+}
 
-            if down == 0 {
-                down = 26;
+/// Accented Latin letters as their plain ones; anything else is left as it is.
+fn transliterate(from: &str) -> String {
+    let mut out = String::with_capacity(from.len());
+    for c in from.chars().flat_map(char::to_lowercase) {
+        let plain = match c {
+            'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => "a",
+            'æ' => "ae",
+            'ç' => "c",
+            'è' | 'é' | 'ê' | 'ë' => "e",
+            'ì' | 'í' | 'î' | 'ï' => "i",
+            'ñ' => "n",
+            'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' => "o",
+            'œ' => "oe",
+            'ß' => "ss",
+            'ù' | 'ú' | 'û' | 'ü' => "u",
+            'ý' | 'ÿ' => "y",
+            c => {
+                out.push(c);
+                continue;
             }
+        };
+        out.push_str(plain);
+    }
+    out
+}
 
-            ret[i] = Some(Word::raw(w | u64::from(up) << shift));
-            ret[i + 6] = Some(Word::raw(w | u64::from(down) << shift));
+/// One word per line; blank lines are ignored, and words that won't fit in a
+/// `Word` are handed back with why, rather than failing the whole list.
+fn parse_dictionary(input: impl BufRead) -> io::Result<(WordSet, Vec<(String, WordError)>)> {
+    let mut words = WordSet::default();
+    let mut skipped = Vec::new();
+    for line in input.lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match Word::try_new(line) {
+            Ok(word) => {
+                words.insert(word);
+            }
+            Err(e) => skipped.push((line.to_string(), e)),
         }
-        ret
     }
+    Ok((words, skipped))
 }
 
-fn main() {
-    let mut lines = include_str!("../input.txt").split('\n');
-    let mut inputs = Vec::new();
-    for line in lines {
-        if line.is_empty() {
-            break;
+/// Solves each `left right` line as it's read, with the one `solver` kept
+/// warm throughout, until `quit` or the end of `input`.
+fn repl(
+    input: impl BufRead,
+    out: &mut impl Write,
+    solver: &mut Solver,
+    prompt: bool,
+) -> io::Result<()> {
+    let mut lines = input.lines();
+    loop {
+        if prompt {
+            write!(out, "> ")?;
+            out.flush()?;
+        }
+        let Some(line) = lines.next() else {
+            return Ok(());
+        };
+        let line = line?;
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let (left, right) = match words[..] {
+            [] => continue,
+            ["quit"] => return Ok(()),
+            [left, right] => (left, right),
+            _ => {
+                writeln!(out, "expected two words, found {}", words.len())?;
+                continue;
+            }
+        };
+        match (Word::try_new(left), Word::try_new(right)) {
+            (Ok(left), Ok(right)) => {
+                let output = Output {
+                    quiet: true,
+                    ..Output::default()
+                };
+                let result = solve_pair(solver, left, right, &output);
+                writeln!(out, "{}", result.text())?;
+            }
+            (Err(e), _) | (_, Err(e)) => writeln!(out, "{} {}: {}", left, right, e)?,
         }
-        let mut line = line.split(' ');
-        let left = line.next().unwrap();
-        let right = line.next().unwrap();
-        inputs.push((left, right));
     }
+}
 
-    inputs.sort_by_key(|(left, right)| left.len().max(right.len()));
+/// Every pair, in order, across `jobs` threads. Each thread has a `Solver`
+/// of its own, with `solver`'s config and filter but not its cache; their
+/// totals end up in `solver`'s.
+#[cfg(feature = "parallel")]
+fn solve_in_parallel(
+    solver: &mut Solver,
+    inputs: Vec<(Word, Word)>,
+    jobs: usize,
+    args: &Args,
+) -> Vec<PairResult> {
+    use rayon::prelude::*;
+    use std::mem;
+    use uwcsmini::RunStats;
 
-    for (left, right) in inputs {
-        print_path(&left.to_ascii_lowercase(), &right.to_ascii_lowercase());
-    }
+    let pool = match rayon::ThreadPoolBuilder::new().num_threads(jobs).build() {
+        Ok(pool) => pool,
+        Err(e) => {
+            eprintln!("can't start {} threads: {}", jobs, e);
+            process::exit(1);
+        }
+    };
+    let (config, filter) = (solver.config, &solver.filter);
+    let solved: Vec<(PairResult, RunStats)> = pool.install(|| {
+        inputs
+            .into_par_iter()
+            .map_init(
+                || {
+                    let mut solver = Solver::with_config(config);
+                    solver.filter = filter.clone();
+                    solver
+                },
+                |solver, (left, right)| {
+                    let result = solve_pair(solver, left, right, &args.output);
+                    (result, mem::take(&mut solver.totals))
+                },
+            )
+            .collect()
+    });
+    solved
+        .into_iter()
+        .map(|(result, totals)| {
+            solver.totals.merge(totals);
+            result
+        })
+        .collect()
 }
 
-fn print_path(left: &str, right: &str) {
+fn solve_pair(solver: &mut Solver, starter: Word, target: Word, output: &Output) -> PairResult {
+    let quiet = output.quiet;
     let start = Instant::now();
-    let mut m = HashMap::with_capacity(10_000_000);
-    println!("trying {} -> {}", left, right);
-    let starter = Word::new(left);
-    let target = Word::new(right);
-    m.insert(starter, starter);
-    let len_limit = (left.len().max(right.len())) as u8;
-
-    let mut new_words: Vec<Word> = Vec::with_capacity(100);
-    new_words.push(starter);
-    for it in 1..32u8 {
-        let old_words = new_words.clone();
-        new_words.clear();
-        for k in old_words {
-            let mut appl = |op: Option<Word>| {
-                if let Some(word) = op {
-                    if let Entry::Vacant(v) = m.entry(word) {
-                        v.insert(k);
-                        new_words.push(word);
-                    }
-                }
-            };
-            appl(k.dupl_first(len_limit));
-            appl(k.pop());
-            for op in k.shifts() {
-                appl(op);
+    if !quiet {
+        eprintln!("trying {} -> {}", starter, target);
+    }
+
+    let interval = output.progress_interval.unwrap_or(1);
+    let result = solver.solve_with_progress(
+        starter,
+        target,
+        every_nth_level(interval, |stat| {
+            if !quiet {
+                eprintln!("{}: {} {}", stat.depth, stat.frontier, stat.visited)
             }
-            for op in k.rotate() {
-                appl(op);
+        }),
+    );
+    let timed_out = result.as_ref().is_ok_and(|result| result.timed_out);
+    let max_steps = match &result {
+        Ok(SolveResult {
+            retried_at: Some(steps),
+            ..
+        }) => *steps,
+        _ => solver.config.max_steps,
+    };
+    if let Ok(SolveResult {
+        retried_at: Some(steps),
+        path,
+        ..
+    }) = &result
+    {
+        if !quiet {
+            let outcome = if path.is_some() { "found" } else { "nothing" };
+            eprintln!(
+                "out of steps on {} -> {}; retried with {}: {}",
+                starter, target, steps, outcome
+            );
+        }
+    }
+    let found = result
+        .map(|result| {
+            if output.reverse_output {
+                result.reversed()
+            } else {
+                result
             }
+        })
+        .map(SolveResult::into_ladder);
+    PairResult {
+        left: starter,
+        right: target,
+        found,
+        elapsed: Instant::now() - start,
+        backwards: output.reverse_output,
+        timed_out,
+        max_steps,
+    }
+}
+
+/// Passes on only every `interval`th level to `report`, counting from the
+/// first, so a long search doesn't spend its time printing. The count goes
+/// by depth, so starts again with a retry's fresh search.
+fn every_nth_level(interval: u32, mut report: impl FnMut(LevelStat)) -> impl FnMut(LevelStat) {
+    move |stat| {
+        if stat.depth % interval == 0 {
+            report(stat);
         }
+    }
+}
 
-        // println!("{:?} {:?}", new_words, m);
+fn print_result(result: &PairResult, log: &mut Log, args: &Args) {
+    let line = result.text();
+    match args.output.format {
+        Format::Text => println!("{}", line),
+        Format::Json => println!("{}", result.json()),
+        Format::Csv => println!("{}", result.csv()),
+    }
+    if let Err(e) = log.line(&line) {
+        eprintln!("can't write log: {}", e);
+    }
+    if let Some(dir) = &args.out_dir {
+        if let Err(e) = result.write_path(Path::new(dir)) {
+            eprintln!(
+                "can't write {} -> {} to {}: {}",
+                result.left, result.right, dir, e
+            );
+        }
+    }
+}
+
+/// Fewest moves first, then those with no ladder, each group in its old order.
+fn sort_by_steps(results: &mut [PairResult]) {
+    results.sort_by_key(|result| result.steps().unwrap_or(usize::MAX));
+}
 
-        if m.contains_key(&target) {
-            break;
+/// Counts pairs by how many moves their ladder took, `None` for no ladder.
+fn tally(lengths: &mut BTreeMap<Option<u32>, usize>, result: &PairResult) {
+    let steps = result.steps().map(|steps| steps as u32);
+    *lengths.entry(steps).or_default() += 1;
+}
+
+/// A bar per ladder length, widest scaled to 40 columns, unsolved last.
+fn histogram(lengths: &BTreeMap<Option<u32>, usize>) -> String {
+    let widest = lengths.values().copied().max().unwrap_or(0);
+    let bar = |count: usize| "#".repeat((count * 40).div_ceil(widest.max(1)));
+    let mut out = String::new();
+    for (steps, &count) in lengths.iter().filter(|(steps, _)| steps.is_some()) {
+        let steps = steps.unwrap_or_default();
+        out.push_str(&format!("{:>8} {:>5} {}\n", steps, count, bar(count)));
+    }
+    if let Some(&count) = lengths.get(&None) {
+        out.push_str(&format!("{:>8} {:>5} {}\n", "unsolved", count, bar(count)));
+    }
+    out
+}
+
+/// `<left>_<right>.path`, keeping only lowercase letters and digits so a name
+/// can't climb out of the directory.
+fn path_file_name(left: &str, right: &str) -> String {
+    let clean = |s: &str| -> String {
+        s.chars()
+            .map(|c| c.to_ascii_lowercase())
+            .filter(|c| c.is_ascii_lowercase() || c.is_ascii_digit())
+            .collect()
+    };
+    format!("{}_{}.path", clean(left), clean(right))
+}
+
+/// How one pair went, for the machine-readable formats.
+struct PairResult {
+    left: Word,
+    right: Word,
+    found: Result<Option<Ladder>, SolveError>,
+    elapsed: Duration,
+    /// The ladder runs from `right` back to `left`.
+    backwards: bool,
+    /// Abandoned at `--timeout-ms` without a ladder.
+    timed_out: bool,
+    /// The step cap searched to, which is the retry's if there was one.
+    max_steps: u32,
+}
+
+const CSV_HEADER: &str = "left,right,steps,found,elapsed_ms,path";
+
+impl PairResult {
+    /// The free-form line for `--format text` and the log.
+    fn text(&self) -> String {
+        match &self.found {
+            Ok(Some((path, ops))) => format!(
+                "{} {} {:?}",
+                path.len(),
+                if self.backwards {
+                    format_reversed_ladder(path, ops)
+                } else {
+                    format_ladder(path, ops)
+                },
+                self.elapsed
+            ),
+            Ok(None) if self.timed_out => format!(
+                "timed out on {} -> {} {:?}",
+                self.left, self.right, self.elapsed
+            ),
+            Ok(None) => format!(
+                "no path found from {} to {} within {} steps {:?}",
+                self.left, self.right, self.max_steps, self.elapsed,
+            ),
+            Err(e) => format!(
+                "gave up on {} -> {}: {} {:?}",
+                self.left, self.right, e, self.elapsed
+            ),
+        }
+    }
+
+    fn path(&self) -> &[Word] {
+        match &self.found {
+            Ok(Some((path, _))) => path,
+            _ => &[],
         }
+    }
 
-        println!("{}: {} {}", it, new_words.len(), m.len(),);
+    fn steps(&self) -> Option<usize> {
+        self.path().len().checked_sub(1)
     }
 
-    let mut path = Vec::with_capacity(32);
-    let mut curr = target;
-    path.push(curr);
-    while let Some(word) = m.get(&curr) {
-        path.push(*word);
-        if *word == starter {
-            break;
+    /// One JSON object, for `--format json`.
+    fn json(&self) -> String {
+        let path: Vec<String> = self
+            .path()
+            .iter()
+            .map(|w| json_string(&w.to_string()))
+            .collect();
+        let mut line = format!(
+            "{{\"left\":{},\"right\":{},\"steps\":{},\"path\":[{}],\"elapsed_ms\":{},\"found\":{}",
+            json_string(&self.left.to_string()),
+            json_string(&self.right.to_string()),
+            self.steps()
+                .map_or_else(|| "null".to_string(), |s| s.to_string()),
+            path.join(","),
+            self.elapsed.as_millis(),
+            self.steps().is_some(),
+        );
+        if let Err(e) = &self.found {
+            line.push_str(&format!(",\"error\":{}", json_string(&e.to_string())));
+        }
+        if self.timed_out {
+            line.push_str(",\"timed_out\":true");
         }
-        curr = *word;
+        line.push('}');
+        line
     }
 
-    path.reverse();
-    log(&format!(
-        "{} {:?} {:?}",
-        path.len(),
-        path,
-        Instant::now() - start,
-    ));
+    /// The ladder, a word per line, to its own file in `dir`. Nothing to write
+    /// without one.
+    fn write_path(&self, dir: &Path) -> io::Result<()> {
+        if self.path().is_empty() {
+            return Ok(());
+        }
+        fs::create_dir_all(dir)?;
+        let name = path_file_name(&self.left.to_string(), &self.right.to_string());
+        let mut out = BufWriter::new(fs::File::create(dir.join(name))?);
+        for word in self.path() {
+            writeln!(out, "{}", word)?;
+        }
+        out.flush()
+    }
+
+    /// One row under `CSV_HEADER`, for `--format csv`.
+    fn csv(&self) -> String {
+        let path: Vec<String> = self.path().iter().map(|w| w.to_string()).collect();
+        [
+            self.left.to_string(),
+            self.right.to_string(),
+            self.steps().map_or_else(String::new, |s| s.to_string()),
+            self.steps().is_some().to_string(),
+            self.elapsed.as_millis().to_string(),
+            path.join("->"),
+        ]
+        .iter()
+        .map(|field| csv_field(field))
+        .collect::<Vec<_>>()
+        .join(",")
+    }
 }
 
-fn log(line: &str) {
-    println!("{}", line);
-    let mut file = fs::OpenOptions::new()
-        .append(true)
-        .create(true)
-        .open("log.log")
-        .unwrap();
-    file.write_all(line.as_bytes()).unwrap();
-    file.write_all(b"\n").unwrap();
-    file.flush().unwrap();
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
 }
 
-#[test]
-fn lens() {
-    assert_eq!(1, Word::new("a").len());
-    assert_eq!(1, Word::new("z").len());
-    assert_eq!(2, Word::new("aa").len());
-    assert_eq!(2, Word::new("zz").len());
-    assert_eq!(7, Word::new("aaaaaaa").len());
-    assert_eq!(7, Word::new("zzzzzzz").len());
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            c if c.is_control() => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
 }
 
-#[test]
-fn strs() {
-    assert_eq!("a", format!("{:?}", Word::new("a")));
-    assert_eq!("ab", format!("{:?}", Word::new("ab")));
-    assert_eq!("abcde", format!("{:?}", Word::new("abcde")));
-    assert_eq!("abcdefghi", format!("{:?}", Word::new("abcdefghi")));
+/// Where result lines are appended when no `--log` is given.
+const DEFAULT_LOG_PATH: &str = "log.log";
+
+/// The log file, opened once for the whole run.
+struct Log {
+    out: BufWriter<fs::File>,
+}
+
+impl Log {
+    fn open(path: impl AsRef<Path>) -> io::Result<Log> {
+        let file = fs::OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(path)?;
+        Ok(Log {
+            out: BufWriter::new(file),
+        })
+    }
+
+    /// Flushed per line, so a run cut short still leaves everything it finished.
+    fn line(&mut self, line: &str) -> io::Result<()> {
+        writeln!(self.out, "{}", line)?;
+        self.out.flush()
+    }
 }
 
 #[test]
-fn dupl() {
-    assert_eq!(Some(Word::new("aa")), Word::new("a").dupl_first(8));
-    assert_eq!(Some(Word::new("aab")), Word::new("ab").dupl_first(8));
-    assert_eq!(Some(Word::new("aabcde")), Word::new("abcde").dupl_first(8));
-    assert_eq!(None, Word::new("abcdefgh").dupl_first(8));
+fn log_to_path() {
+    let path = env::temp_dir().join(format!("uwcsmini-log-{}.log", process::id()));
+    let _ = fs::remove_file(&path);
+    {
+        let mut log = Log::open(&path).unwrap();
+        log.line("1 a").unwrap();
+        log.line("2 a --dupl-> aa").unwrap();
+    }
+    Log::open(&path).unwrap().line("appended").unwrap();
+    assert_eq!(
+        "1 a\n2 a --dupl-> aa\nappended\n",
+        fs::read_to_string(&path).unwrap()
+    );
+    fs::remove_file(&path).unwrap();
 }
 
 #[test]
-fn dupl_limit() {
-    assert_eq!(None, Word::new("a").dupl_first(1));
-    assert_eq!(None, Word::new("ab").dupl_first(2));
+fn parses_pairs() {
+    let input = io::Cursor::new("SICK TRUE\nab ba\n");
+    assert_eq!(
+        vec![
+            (1, "SICK".to_string(), "TRUE".to_string()),
+            (2, "ab".to_string(), "ba".to_string())
+        ],
+        parse_pairs(input).unwrap().0
+    );
 }
 
 #[test]
-fn poppity() {
-    assert_eq!(Some(Word::new("bcde")), Word::new("abcde").pop());
-    assert_eq!(Some(Word::new("b")), Word::new("ab").pop());
-    assert_eq!(None, Word::new("a").pop());
+fn malformed_lines() {
+    let input = io::Cursor::new("ab ba\nab\nab  ba\nab ba ab\n");
+    let (pairs, rejects) = parse_pairs(input).unwrap();
+    assert_eq!(
+        vec![
+            (1, "ab".to_string(), "ba".to_string()),
+            (3, "ab".to_string(), "ba".to_string())
+        ],
+        pairs
+    );
+    assert_eq!(
+        vec![
+            (2, "expected two words, found 1".to_string()),
+            (4, "expected two words, found 3".to_string()),
+        ],
+        rejects
+    );
 }
 
 #[test]
-fn shifty_edge() {
+fn skips_blanks_and_comments() {
+    let input = io::Cursor::new("# pairs\nab ba\n\n  \nabc bca\n# more\n\nxy yx\n");
     assert_eq!(
-        [
-            Some(Word::new("b")),
-            None,
-            None,
-            None,
-            None,
-            None,
-            Some(Word::new("z")),
-            None,
-            None,
-            None,
-            None,
-            None
+        vec![
+            (2, "ab".to_string(), "ba".to_string()),
+            (5, "abc".to_string(), "bca".to_string()),
+            (8, "xy".to_string(), "yx".to_string()),
         ],
-        Word::new("a").shifts()
+        parse_pairs(input).unwrap().0
     );
+}
+
+#[test]
+fn rejects_bad_words() {
+    let pairs = vec![
+        (1, "abc".to_string(), "bca".to_string()),
+        (2, "a1".to_string(), "b".to_string()),
+        (4, "ab".to_string(), "abcdefghijklm".to_string()),
+    ];
+    let (words, rejects) = to_words(pairs, AsciiMode::Skip);
+    assert_eq!(vec![(Word::new("abc"), Word::new("bca"))], words);
     assert_eq!(
-        [
-            Some(Word::new("a")),
-            None,
-            None,
-            None,
-            None,
-            None,
-            Some(Word::new("y")),
-            None,
-            None,
-            None,
-            None,
-            None
+        vec![
+            (2, "skipping a1 b: invalid character '1' at 1".to_string()),
+            (
+                4,
+                "skipping ab abcdefghijklm: too long: 13 letters".to_string()
+            ),
         ],
-        Word::new("z").shifts()
+        rejects
     );
 }
 
 #[test]
-fn shifty_multiple() {
+fn not_ascii() {
+    let input = io::Cursor::new(b"ab ba\ncaf\xe9 cafe\n\nabc bca\r\n".to_vec());
+    let (pairs, rejects) = parse_pairs(input).unwrap();
+    assert_eq!(vec![(2, "not utf-8: caf\\xe9 cafe".to_string())], rejects);
+    assert_eq!(2, pairs.len());
+    assert_eq!((4, "abc".to_string(), "bca".to_string()), pairs[1]);
+
+    let pairs = vec![
+        (1, "café".to_string(), "cafe".to_string()),
+        (2, "naïve".to_string(), "Œuvre".to_string()),
+        (3, "ab".to_string(), "bä".to_string()),
+        (4, "ab".to_string(), "b→".to_string()),
+    ];
+    let (words, rejects) = to_words(pairs.clone(), AsciiMode::Skip);
+    assert!(words.is_empty());
     assert_eq!(
-        [
-            Some(Word::new("cc")),
-            Some(Word::new("bd")),
-            None,
-            None,
-            None,
-            None,
-            Some(Word::new("ac")),
-            Some(Word::new("bb")),
-            None,
-            None,
-            None,
-            None
+        (
+            1,
+            "skipping café cafe: not ascii: caf\\xc3\\xa9".to_string()
+        ),
+        rejects[0]
+    );
+    assert_eq!(
+        (3, "skipping ab bä: not ascii: b\\xc3\\xa4".to_string()),
+        rejects[2]
+    );
+    assert_eq!(4, rejects.len());
+
+    let (words, rejects) = to_words(pairs, AsciiMode::Transliterate);
+    let pair = |l: &str, r: &str| (Word::new(l), Word::new(r));
+    assert_eq!(
+        vec![
+            pair("cafe", "cafe"),
+            pair("naive", "oeuvre"),
+            pair("ab", "ba")
         ],
-        Word::new("bc").shifts()
+        words
     );
+    assert_eq!(vec![4], rejects.iter().map(|r| r.0).collect::<Vec<_>>());
 }
 
 #[test]
-fn shifty_long() {
+fn neighbours() {
+    let args =
+        |list: &[&str]| neighbours_command(&list.iter().map(|s| s.to_string()).collect::<Vec<_>>());
     assert_eq!(
-        [
-            Some(Word::new("pooooo")),
-            Some(Word::new("opoooo")),
-            Some(Word::new("oopooo")),
-            Some(Word::new("ooopoo")),
-            Some(Word::new("oooopo")),
-            Some(Word::new("ooooop")),
-            Some(Word::new("nooooo")),
-            Some(Word::new("onoooo")),
-            Some(Word::new("oonooo")),
-            Some(Word::new("ooonoo")),
-            Some(Word::new("oooono")),
-            Some(Word::new("ooooon")),
+        "pop: b\nshift: aa ac bb zb\nrotate: ba\nunrotate: ba\nreverse: ba\nswap: ba\n",
+        args(&["ab"]).unwrap()
+    );
+    let wider = args(&["abc", "8"]).unwrap();
+    assert!(wider.starts_with("dupl: aabc\n"), "{}", wider);
+    assert!(wider.contains("rotate: bca\n"), "{}", wider);
+    assert!(args(&[]).is_err());
+    assert!(args(&["ab", "13"]).is_err());
+    assert!(args(&["a1"]).is_err());
+    assert!(args(&["ab", "4", "5"]).is_err());
+}
+
+#[test]
+fn progress_interval() {
+    let depths = |interval: u32| {
+        let mut seen = Vec::new();
+        let mut solver = Solver::new();
+        solver
+            .solve_with_progress(
+                Word::new("abc"),
+                Word::new("bzz"),
+                every_nth_level(interval, |stat| seen.push(stat.depth)),
+            )
+            .unwrap();
+        seen
+    };
+    assert_eq!(vec![1, 2, 3, 4, 5], depths(1));
+    assert_eq!(vec![3], depths(3));
+    assert_eq!(vec![2, 4], depths(2));
+    assert!(depths(6).is_empty());
+}
+
+#[test]
+fn solve_arguments() {
+    let args = |list: &[&str]| solve_args(&list.iter().map(|s| s.to_string()).collect::<Vec<_>>());
+    let (abc, bca) = (Word::new("abc"), Word::new("bca"));
+    assert_eq!(Ok((abc, bca)), args(&["abc", "bca"]));
+    let (raw_abc, raw_bca) = (abc.as_u64().to_string(), bca.as_u64().to_string());
+    assert_eq!(Ok((abc, bca)), args(&["--raw", &raw_abc, &raw_bca]));
+    assert_eq!(
+        Err("0: not a packed word".to_string()),
+        args(&["--raw", "0", &raw_bca])
+    );
+    assert_eq!(
+        Err("abc: not a number".to_string()),
+        args(&["--raw", &raw_abc, "abc"])
+    );
+    assert!(args(&["abc"]).is_err());
+    assert!(args(&["--raw", &raw_abc]).is_err());
+    assert!(args(&["a1", "b"]).is_err());
+}
+
+#[test]
+fn repl_session() {
+    let input = io::Cursor::new("ab ba\n\nab\na1 b\nabc bca\nquit\nab ba\n");
+    let mut out = Vec::new();
+    let mut solver = Solver::with_config(SolverConfig::new().max_nodes(100_000));
+    repl(input, &mut out, &mut solver, false).unwrap();
+    let out = String::from_utf8(out).unwrap();
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(4, lines.len(), "{}", out);
+    assert!(lines[0].starts_with("2 ab --rotate-> ba "), "{}", out);
+    assert_eq!("expected two words, found 1", lines[1]);
+    assert_eq!("a1 b: invalid character '1' at 1", lines[2]);
+    assert!(lines[3].starts_with("2 abc --rotate-> bca "), "{}", out);
+}
+
+#[cfg(feature = "gzip")]
+#[test]
+fn reads_gzip() {
+    use flate2::write::GzEncoder;
+
+    let dir = env::temp_dir().join(format!("uwcsmini-gzip-{}", process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    let pairs = "SICK TRUE\n# comment\nab ba\n";
+    let plain = dir.join("pairs.txt");
+    fs::write(&plain, pairs).unwrap();
+    let gz = dir.join("pairs.txt.gz");
+    let mut encoder = GzEncoder::new(fs::File::create(&gz).unwrap(), Default::default());
+    encoder.write_all(pairs.as_bytes()).unwrap();
+    encoder.finish().unwrap();
+
+    let read = |path: &Path| parse_pairs(open_input(path.to_str().unwrap()).unwrap()).unwrap();
+    assert_eq!(read(&plain), read(&gz));
+    assert_eq!(2, read(&gz).0.len());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[cfg(not(feature = "gzip"))]
+#[test]
+fn gzip_needs_feature() {
+    let path = env::temp_dir().join(format!("uwcsmini-nogzip-{}.gz", process::id()));
+    fs::write(&path, "").unwrap();
+    let err = open_input(path.to_str().unwrap()).err().unwrap();
+    assert_eq!(io::ErrorKind::Unsupported, err.kind());
+    fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn parses_dictionary() {
+    let input = "abc\n\nabd\nabcdefghijklm\nit's\n";
+    let (words, skipped) = parse_dictionary(input.as_bytes()).unwrap();
+    let expected: WordSet = [Word::new("abc"), Word::new("abd")].into_iter().collect();
+    assert_eq!(expected, words);
+    assert_eq!(
+        vec![
+            ("abcdefghijklm".to_string(), WordError::TooLong { len: 13 }),
+            (
+                "it's".to_string(),
+                WordError::InvalidChar { ch: '\'', index: 2 }
+            ),
         ],
-        Word::new("oooooo").shifts()
+        skipped
+    );
+}
+
+#[test]
+fn args() {
+    let args = |list: &[&str]| parse_args(list.iter().map(|s| s.to_string()));
+    assert_eq!(Ok(Args::default()), args(&[]));
+    assert_eq!(
+        Ok(Args {
+            input: Some("pairs.txt".to_string()),
+            output: Output {
+                format: Format::Json,
+                quiet: false,
+                reverse_output: false,
+                progress_interval: None,
+            },
+            log: None,
+            out_dir: None,
+            no_cache: false,
+            sort_by_steps: false,
+            dict: None,
+            dot: None,
+            dot_near: None,
+            max_len: None,
+            check: false,
+            strict: false,
+            timeout: None,
+            seed: None,
+            queue: false,
+            ascii_only: AsciiMode::Skip,
+            jobs: None,
+            limit: None,
+            no_wrap: false,
+        }),
+        args(&["--format", "json", "pairs.txt"])
+    );
+    assert_eq!(
+        Some("run.log".to_string()),
+        args(&["--log", "run.log"]).unwrap().log
+    );
+    assert!(args(&["--log"]).is_err());
+    assert_eq!(
+        Some("words.txt".to_string()),
+        args(&["--dict", "words.txt"]).unwrap().dict
+    );
+    assert!(args(&["--dict"]).is_err());
+    let dot = args(&["--dot", "tree.dot", "--dot-near", "2"]).unwrap();
+    assert_eq!(
+        (Some("tree.dot".to_string()), Some(2)),
+        (dot.dot, dot.dot_near)
+    );
+    assert!(args(&["--dot-near", "far"]).is_err());
+    assert_eq!(Some(4), args(&["--max-len", "4"]).unwrap().max_len);
+    assert!(args(&["--check"]).unwrap().check);
+    assert!(args(&["--strict"]).unwrap().strict);
+    assert!(args(&["--reverse-output"]).unwrap().output.reverse_output);
+    assert_eq!(
+        Some(Duration::from_millis(250)),
+        args(&["--timeout-ms", "250"]).unwrap().timeout
+    );
+    assert!(args(&["--timeout-ms", "soon"]).is_err());
+    assert_eq!(Some(42), args(&["--seed", "42"]).unwrap().seed);
+    assert!(args(&["--seed", "-1"]).is_err());
+    assert!(args(&["--queue"]).unwrap().queue);
+    assert_eq!(
+        AsciiMode::Transliterate,
+        args(&["--ascii-only", "transliterate"]).unwrap().ascii_only
+    );
+    assert!(args(&["--ascii-only", "drop"]).is_err());
+    assert_eq!(
+        Some(3),
+        args(&["--progress-interval", "3"])
+            .unwrap()
+            .output
+            .progress_interval
+    );
+    assert!(args(&["--progress-interval", "0"]).is_err());
+    assert!(args(&["--jobs", "0"]).is_err());
+    assert_eq!(Some(0), args(&["--limit", "0"]).unwrap().limit);
+    assert!(args(&["--limit", "all"]).is_err());
+    assert!(args(&["--no-wrap"]).unwrap().no_wrap);
+    if cfg!(feature = "parallel") {
+        assert_eq!(Some(4), args(&["--jobs", "4"]).unwrap().jobs);
+        assert!(args(&["--jobs", "4", "--dot", "tree.dot"]).is_err());
+    } else {
+        assert!(args(&["--jobs", "4"]).is_err());
+    }
+    assert!(args(&["--max-len", "0"]).is_err());
+    assert!(args(&["--max-len", "13"]).is_err());
+    assert!(args(&["--quiet", "-"]).unwrap().output.quiet);
+    assert!(args(&["-q"]).unwrap().output.quiet);
+    assert_eq!(Some("-".to_string()), args(&["-"]).unwrap().input);
+    assert!(args(&["--format", "xml"]).is_err());
+    assert!(args(&["--format"]).is_err());
+    assert!(args(&["--wat"]).is_err());
+    assert!(args(&["a", "b"]).is_err());
+}
+
+#[cfg(test)]
+fn ladder_result() -> PairResult {
+    let (left, right) = (Word::new("abc"), Word::new("bda"));
+    let path = vec![left, Word::new("bca"), right];
+    let ops = vec![Op::RotateLeft, Op::Shift { index: 1, up: true }];
+    PairResult {
+        left,
+        right,
+        found: Ok(Some((path, ops))),
+        elapsed: Duration::from_millis(12),
+        backwards: false,
+        timed_out: false,
+        max_steps: DEFAULT_MAX_STEPS,
+    }
+}
+
+#[test]
+fn json_lines() {
+    let mut result = ladder_result();
+    let parsed: serde_json::Value = serde_json::from_str(&result.json()).unwrap();
+    assert_eq!(
+        serde_json::json!({
+            "left": "abc",
+            "right": "bda",
+            "steps": 2,
+            "path": ["abc", "bca", "bda"],
+            "elapsed_ms": 12,
+            "found": true,
+        }),
+        parsed
+    );
+
+    result.found = Ok(None);
+    let parsed: serde_json::Value = serde_json::from_str(&result.json()).unwrap();
+    assert_eq!(serde_json::Value::Bool(false), parsed["found"]);
+    assert_eq!(serde_json::Value::Null, parsed["steps"]);
+
+    result.found = Err(SolveError::Budget { explored: 7 });
+    let parsed: serde_json::Value = serde_json::from_str(&result.json()).unwrap();
+    assert_eq!("out of budget after exploring 7 words", parsed["error"]);
+}
+
+#[test]
+fn csv_rows() {
+    let mut result = ladder_result();
+    assert_eq!(6, CSV_HEADER.split(',').count());
+    let row = result.csv();
+    let fields: Vec<&str> = row.split(',').collect();
+    assert_eq!(
+        vec!["abc", "bda", "2", "true", "12", "abc->bca->bda"],
+        fields
+    );
+
+    result.found = Ok(None);
+    assert_eq!("abc,bda,,false,12,", result.csv());
+}
+
+#[test]
+fn path_file_names() {
+    assert_eq!("abc_bda.path", path_file_name("abc", "bda"));
+    assert_eq!("abc_etcpasswd.path", path_file_name("ABC", "../etc/passwd"));
+}
+
+#[test]
+fn writes_paths() {
+    let dir = env::temp_dir().join(format!("uwcsmini-paths-{}", process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    let mut result = ladder_result();
+    result.write_path(&dir).unwrap();
+    assert_eq!(
+        "abc\nbca\nbda\n",
+        fs::read_to_string(dir.join("abc_bda.path")).unwrap()
     );
+
+    result.left = Word::new("xyz");
+    result.found = Ok(None);
+    result.write_path(&dir).unwrap();
+    assert!(!dir.join("xyz_bda.path").exists());
+    fs::remove_dir_all(&dir).unwrap();
 }
 
 #[test]
-fn rotter() {
-    assert_eq!([None, None], Word::new("a").rotate());
+fn sorted_by_steps() {
+    let unsolved = PairResult {
+        found: Ok(None),
+        ..ladder_result()
+    };
+    let one = PairResult {
+        left: Word::new("abc"),
+        right: Word::new("bca"),
+        found: Ok(Some((
+            vec![Word::new("abc"), Word::new("bca")],
+            vec![Op::RotateLeft],
+        ))),
+        elapsed: Duration::ZERO,
+        backwards: false,
+        timed_out: false,
+        max_steps: DEFAULT_MAX_STEPS,
+    };
+    let mut results = vec![unsolved, ladder_result(), one];
+    sort_by_steps(&mut results);
+    let steps: Vec<Option<usize>> = results.iter().map(PairResult::steps).collect();
+    assert_eq!(vec![Some(1), Some(2), None], steps);
+}
+
+#[test]
+fn length_histogram() {
+    let ladder = |words: &[&str]| PairResult {
+        found: Ok(Some((
+            words.iter().map(|w| Word::new(w)).collect(),
+            vec![Op::RotateLeft; words.len() - 1],
+        ))),
+        ..ladder_result()
+    };
+    let results = vec![
+        ladder(&["abc", "bca"]),
+        ladder_result(),
+        ladder(&["ab"]),
+        PairResult {
+            found: Ok(None),
+            ..ladder_result()
+        },
+        ladder(&["ab", "ba"]),
+        PairResult {
+            found: Err(SolveError::Budget { explored: 3 }),
+            ..ladder_result()
+        },
+    ];
+    let mut lengths = BTreeMap::new();
+    for result in &results {
+        tally(&mut lengths, result);
+    }
     assert_eq!(
-        [Some(Word::new("aa")), Some(Word::new("aa"))],
-        Word::new("aa").rotate()
+        BTreeMap::from([(None, 2), (Some(0), 1), (Some(1), 2), (Some(2), 1)]),
+        lengths
     );
     assert_eq!(
-        [Some(Word::new("ba")), Some(Word::new("ba"))],
-        Word::new("ab").rotate()
+        concat!(
+            "       0     1 ####################\n",
+            "       1     2 ########################################\n",
+            "       2     1 ####################\n",
+            "unsolved     2 ########################################\n",
+        ),
+        histogram(&lengths)
     );
+    assert_eq!("", histogram(&BTreeMap::new()));
+}
+
+#[test]
+fn dedups_pairs() {
+    let pair = |l: &str, r: &str| (Word::new(l), Word::new(r));
+    let (kept, skipped) = dedup_pairs(vec![
+        pair("ab", "ba"),
+        pair("abc", "bca"),
+        pair("ab", "ba"),
+        pair("ba", "ab"),
+        pair("ab", "ba"),
+    ]);
     assert_eq!(
-        [Some(Word::new("bca")), Some(Word::new("cab"))],
-        Word::new("abc").rotate()
+        vec![pair("ab", "ba"), pair("abc", "bca"), pair("ba", "ab")],
+        kept
     );
+    assert_eq!(2, skipped);
+}
+
+#[test]
+fn csv_quoting() {
+    assert_eq!("abc", csv_field("abc"));
+    assert_eq!(r#""a,b""#, csv_field("a,b"));
+    assert_eq!(r#""say ""hi""""#, csv_field(r#"say "hi""#));
+}
+
+#[test]
+fn json_escapes() {
+    assert_eq!(r#""a\"b\\c\u000a""#, json_string("a\"b\\c\n"));
 }
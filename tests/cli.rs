@@ -0,0 +1,653 @@
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::{env, fs, process};
+
+use uwcsmini::Word;
+
+/// A fresh directory to run the binary in, so its log file lands there too.
+fn scratch(name: &str) -> PathBuf {
+    let dir = env::temp_dir().join(format!("uwcsmini-{}-{}", name, process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn run(dir: &Path, args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_uwcsmini"))
+        .args(args)
+        .current_dir(dir)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn solves_named_file() {
+    let dir = scratch("named");
+    fs::write(dir.join("pairs.txt"), "abc bca\nab ba\n").unwrap();
+    let out = run(&dir, &["pairs.txt"]);
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("abc --rotate-> bca"), "{}", stdout);
+    assert!(stdout.contains("2 ab --rotate-> ba"), "{}", stdout);
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(stderr.contains("trying ab -> ba"), "{}", stderr);
+    let log = fs::read_to_string(dir.join("log.log")).unwrap();
+    assert_eq!(2, log.lines().count());
+}
+
+fn run_with_stdin(dir: &Path, args: &[&str], stdin: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_uwcsmini"))
+        .args(args)
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child
+        .stdin
+        .take()
+        .unwrap()
+        .write_all(stdin.as_bytes())
+        .unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn reads_piped_stdin() {
+    let dir = scratch("piped");
+    // Piped input wins over a default input.txt.
+    fs::write(dir.join("input.txt"), "ab ba\n").unwrap();
+    let out = run_with_stdin(&dir, &[], "abc bca\n");
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("abc --rotate-> bca"), "{}", stdout);
+    assert!(!stdout.contains("ab --rotate-> ba"), "{}", stdout);
+}
+
+#[test]
+fn reads_stdin_for_dash() {
+    let dir = scratch("dash");
+    let out = run_with_stdin(&dir, &["-"], "abc bca\n");
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("abc --rotate-> bca"), "{}", stdout);
+}
+
+#[test]
+fn missing_file() {
+    let dir = scratch("missing");
+    let out = run(&dir, &["nope.txt"]);
+    assert!(!out.status.success());
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(stderr.contains("can't read nope.txt"), "{}", stderr);
+}
+
+#[test]
+fn json_format() {
+    let dir = scratch("json");
+    let out = run_with_stdin(&dir, &["--format", "json", "-"], "abc bca\n");
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(1, lines.len(), "{}", stdout);
+    let parsed: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+    assert_eq!("abc", parsed["left"]);
+    assert_eq!(1, parsed["steps"]);
+    assert_eq!(serde_json::json!(["abc", "bca"]), parsed["path"]);
+    assert_eq!(true, parsed["found"]);
+}
+
+#[test]
+fn bad_args() {
+    let dir = scratch("bad-args");
+    let out = run(&dir, &["--format", "xml"]);
+    assert_eq!(Some(2), out.status.code());
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(stderr.contains("usage:"), "{}", stderr);
+}
+
+#[test]
+fn log_path() {
+    let dir = scratch("log-path");
+    let out = run_with_stdin(&dir, &["--log", "custom.log", "-"], "abc bca\nab ba\n");
+    assert!(out.status.success());
+    assert!(!dir.join("log.log").exists());
+    let log = fs::read_to_string(dir.join("custom.log")).unwrap();
+    let lines: Vec<&str> = log.lines().collect();
+    assert_eq!(2, lines.len(), "{}", log);
+    assert!(lines[0].starts_with("2 ab --rotate-> ba "), "{}", log);
+    assert!(lines[1].starts_with("2 abc --rotate-> bca "), "{}", log);
+}
+
+#[test]
+fn log_path_from_env() {
+    let dir = scratch("log-env");
+    let mut child = Command::new(env!("CARGO_BIN_EXE_uwcsmini"))
+        .arg("-")
+        .env("UWCSMINI_LOG", "env.log")
+        .current_dir(&dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(b"ab ba\n").unwrap();
+    assert!(child.wait().unwrap().success());
+    let log = fs::read_to_string(dir.join("env.log")).unwrap();
+    assert_eq!(1, log.lines().count());
+}
+
+#[test]
+fn csv_format() {
+    let dir = scratch("csv");
+    let out = run_with_stdin(&dir, &["--format", "csv", "-"], "abc bca\nab ba\n");
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    let rows: Vec<Vec<&str>> = stdout.lines().map(|l| l.split(',').collect()).collect();
+    assert_eq!(3, rows.len(), "{}", stdout);
+    assert_eq!(
+        vec!["left", "right", "steps", "found", "elapsed_ms", "path"],
+        rows[0]
+    );
+    assert!(rows.iter().all(|row| row.len() == 6), "{}", stdout);
+    assert_eq!(["ab", "ba", "1", "true"], rows[1][..4]);
+    assert_eq!("ab->ba", rows[1][5]);
+}
+
+/// Lines like `3: 120 400` from each level of the search.
+fn level_lines(stderr: &str) -> usize {
+    stderr
+        .lines()
+        .filter(|line| {
+            line.split_once(": ")
+                .is_some_and(|(depth, _)| depth.parse::<u32>().is_ok())
+        })
+        .count()
+}
+
+#[test]
+fn quiet() {
+    let dir = scratch("quiet");
+    let out = run_with_stdin(&dir, &["-"], "abc zcad\n");
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert_eq!(5, level_lines(&stderr), "{}", stderr);
+
+    let out = run_with_stdin(&dir, &["--quiet", "-"], "abc zcad\n");
+    assert!(out.status.success());
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert_eq!("", stderr);
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.starts_with("6 abc --"), "{}", stdout);
+    let log = fs::read_to_string(dir.join("log.log")).unwrap();
+    assert_eq!(2, log.lines().count());
+}
+
+#[test]
+fn out_dir() {
+    let dir = scratch("out-dir");
+    let out = run_with_stdin(&dir, &["--out-dir", "paths", "-"], "abc zcad\nab ba\n");
+    assert!(out.status.success());
+    let ladder = fs::read_to_string(dir.join("paths").join("abc_zcad.path")).unwrap();
+    assert_eq!(6, ladder.lines().count(), "{}", ladder);
+    assert_eq!(Some("zcad"), ladder.lines().last());
+    assert!(dir.join("paths").join("ab_ba.path").exists());
+}
+
+#[test]
+fn sort_by_steps() {
+    let dir = scratch("sort-by-steps");
+    // Shorter words go first, but the longer pair is a single rotation.
+    let input = "abc zcad\nabcdef bcdefa\n";
+    let out = run_with_stdin(&dir, &["--quiet", "-"], input);
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    let firsts: Vec<&str> = stdout
+        .lines()
+        .map(|l| l.split(' ').nth(1).unwrap())
+        .collect();
+    assert_eq!(vec!["abc", "abcdef"], firsts);
+
+    let out = run_with_stdin(&dir, &["--quiet", "--sort-by-steps", "-"], input);
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    let lengths: Vec<&str> = stdout
+        .lines()
+        .map(|l| l.split(' ').next().unwrap())
+        .collect();
+    assert_eq!(vec!["2", "6"], lengths, "{}", stdout);
+}
+
+#[test]
+fn duplicate_pairs() {
+    let dir = scratch("duplicates");
+    let out = run_with_stdin(&dir, &["-"], "ab ba\nabc bca\nAB BA\nab ba\n");
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert_eq!(2, stdout.lines().count(), "{}", stdout);
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert_eq!(1, stderr.matches("trying ab -> ba").count(), "{}", stderr);
+    assert!(stderr.contains("skipping 2 duplicate pairs"), "{}", stderr);
+}
+
+#[test]
+fn cache() {
+    let dir = scratch("cache");
+    let first = run_with_stdin(&dir, &["-"], "abc zcad\n");
+    assert!(first.status.success());
+    let cache = fs::read_to_string(dir.join("cache.txt")).unwrap();
+    assert!(cache.starts_with("abc zcad "), "{}", cache);
+    assert!(
+        cache.trim_end().ends_with(" abc abd acd aacd acad zcad"),
+        "{}",
+        cache
+    );
+
+    let second = run_with_stdin(&dir, &["-"], "abc zcad\n");
+    let stderr = String::from_utf8(second.stderr.clone()).unwrap();
+    assert_eq!(0, level_lines(&stderr), "{}", stderr);
+    let ladder = |out: &Output| {
+        let stdout = String::from_utf8(out.stdout.clone()).unwrap();
+        stdout.rsplit_once(' ').unwrap().0.to_string()
+    };
+    assert_eq!(ladder(&first), ladder(&second));
+
+    let uncached = run_with_stdin(&dir, &["--no-cache", "-"], "abc zcad\n");
+    let stderr = String::from_utf8(uncached.stderr).unwrap();
+    assert_eq!(5, level_lines(&stderr), "{}", stderr);
+}
+
+#[test]
+fn no_cache_file() {
+    let dir = scratch("no-cache");
+    let out = run_with_stdin(&dir, &["--no-cache", "-"], "ab ba\n");
+    assert!(out.status.success());
+    assert!(!dir.join("cache.txt").exists());
+}
+
+#[test]
+fn dictionary() {
+    let dir = scratch("dict");
+    fs::write(dir.join("words.txt"), "abd\nabe\nabcdefghijklmnop\n").unwrap();
+    let out = run_with_stdin(&dir, &["--dict", "words.txt", "-"], "abc abf\n");
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(
+        stdout.contains("abc --shift:3+-> abd --shift:3+-> abe --shift:3+-> abf"),
+        "{}",
+        stdout
+    );
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(
+        stderr.contains("skipping dictionary word abcdefghijklmnop: too long"),
+        "{}",
+        stderr
+    );
+
+    let out = run(&dir, &["--dict", "missing.txt", "-"]);
+    assert_eq!(Some(1), out.status.code());
+}
+
+#[test]
+fn dot() {
+    let dir = scratch("dot");
+    let out = run_with_stdin(
+        &dir,
+        &["--no-cache", "--dot", "tree.dot", "-"],
+        "ab ba\nabc bca\n",
+    );
+    assert!(out.status.success());
+    let dot = fs::read_to_string(dir.join("tree.dot")).unwrap();
+    assert!(dot.starts_with("digraph {\n"), "{}", dot);
+    assert!(dot.ends_with("}\n"), "{}", dot);
+    assert!(dot.contains("\"abc\" [shape=box];"), "{}", dot);
+    assert!(dot.contains("\"bca\" [shape=box];"), "{}", dot);
+    assert!(!dot.contains("\"ba\""), "{}", dot);
+
+    let out = run_with_stdin(
+        &dir,
+        &["--no-cache", "--dot", "near.dot", "--dot-near", "0", "-"],
+        "abc bca\n",
+    );
+    assert!(out.status.success());
+    let near = fs::read_to_string(dir.join("near.dot")).unwrap();
+    assert_eq!(
+        "digraph {\n  \"abc\" [shape=box];\n  \"bca\" [shape=box];\n  \"abc\" -> \"bca\" [label=\"rotate\"];\n}\n",
+        near
+    );
+    assert!(near.len() < dot.len());
+}
+
+#[test]
+fn max_len() {
+    let dir = scratch("max-len");
+    let steps = |args: &[&str]| {
+        let out = run_with_stdin(&dir, args, "abc bzz\n");
+        assert!(out.status.success());
+        let stdout = String::from_utf8(out.stdout).unwrap();
+        stdout.split(' ').next().unwrap().to_string()
+    };
+    assert_eq!("6", steps(&["--no-cache", "-"]));
+    assert_eq!("5", steps(&["--no-cache", "--max-len", "4", "-"]));
+}
+
+#[test]
+fn max_len_below_words() {
+    // Too long to grow, but reversing and rotating keep the length.
+    let dir = scratch("max-len-below");
+    let args = ["--quiet", "--max-len", "3", "-"];
+    for _ in 0..2 {
+        let out = run_with_stdin(&dir, &args, "abcd dcba\nabcd bcda\n");
+        assert!(out.status.success());
+        let stdout = String::from_utf8(out.stdout).unwrap();
+        assert!(stdout.contains("2 abcd --reverse-> dcba"), "{}", stdout);
+        assert!(stdout.contains("2 abcd --rotate-> bcda"), "{}", stdout);
+    }
+    assert!(dir.join("cache.txt").exists());
+}
+
+#[test]
+fn retry() {
+    // Stuck at two letters without wrapping, so 50 and 75 moves.
+    let dir = scratch("retry");
+    let args = [
+        "--no-cache",
+        "--no-wrap",
+        "--max-len",
+        "1",
+        "--progress-interval",
+        "10",
+        "-",
+    ];
+    let out = run_with_stdin(&dir, &args, "aa zz\naaa zzz\n");
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert!(lines[0].starts_with("51 aa "), "{}", stdout);
+    assert!(
+        lines[1].starts_with("no path found from aaa to zzz within 62 steps"),
+        "{}",
+        stdout
+    );
+    let log = fs::read_to_string(dir.join("log.log")).unwrap();
+    assert!(log.contains("within 62 steps"), "{}", log);
+
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(stderr.contains("retried with 62: found"), "{}", stderr);
+    assert!(stderr.contains("retried with 62: nothing"), "{}", stderr);
+    // Each retry counts its levels afresh.
+    let aaa = &stderr[stderr.find("trying aaa").unwrap()..];
+    let depths: Vec<&str> = aaa
+        .lines()
+        .filter_map(|line| line.split_once(": ").map(|(depth, _)| depth))
+        .filter(|depth| depth.parse::<u32>().is_ok())
+        .collect();
+    assert_eq!(
+        vec!["10", "20", "30", "10", "20", "30", "40", "50", "60"],
+        depths
+    );
+}
+
+#[test]
+fn check() {
+    let dir = scratch("check");
+    fs::write(
+        dir.join("pairs.txt"),
+        "abc bca\na1 b\nab ba\nab abcdefghijklm\n",
+    )
+    .unwrap();
+    let out = run(&dir, &["--check", "pairs.txt"]);
+    assert_eq!(Some(1), out.status.code());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert_eq!("2 valid pairs\n", stdout);
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(stderr.contains("line 2: skipping a1 b"), "{}", stderr);
+    assert!(
+        stderr.contains("line 4: skipping ab abcdefghijklm"),
+        "{}",
+        stderr
+    );
+    assert!(!stderr.contains("trying"), "{}", stderr);
+    assert!(!dir.join("log.log").exists());
+
+    fs::write(dir.join("good.txt"), "abc bca\n").unwrap();
+    let out = run(&dir, &["--check", "good.txt"]);
+    assert!(out.status.success());
+    assert_eq!("1 valid pairs\n", String::from_utf8(out.stdout).unwrap());
+}
+
+#[test]
+fn blank_lines() {
+    let dir = scratch("blank-lines");
+    let out = run_with_stdin(&dir, &["--check", "-"], "ab ba\n\n# comment\nabc bca\n");
+    assert!(out.status.success());
+    assert_eq!("2 valid pairs\n", String::from_utf8(out.stdout).unwrap());
+}
+
+#[test]
+fn not_ascii() {
+    let dir = scratch("not-ascii");
+    let input = "ab ba\ncafé cafe\nabc bca\n";
+    let out = run_with_stdin(&dir, &["--format", "csv", "-"], input);
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert_eq!(3, stdout.lines().count(), "{}", stdout);
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(
+        stderr.contains("line 2: skipping café cafe: not ascii: caf\\xc3\\xa9"),
+        "{}",
+        stderr
+    );
+
+    let out = run_with_stdin(
+        &dir,
+        &["--format", "csv", "--ascii-only", "transliterate", "-"],
+        input,
+    );
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("\ncafe,cafe,0,true,"), "{}", stdout);
+}
+
+#[test]
+fn malformed_line() {
+    let dir = scratch("malformed");
+    let input = "ab ba\nabc\nabc bca\n";
+    let out = run_with_stdin(&dir, &["-"], input);
+    assert!(out.status.success());
+    assert_eq!(2, String::from_utf8(out.stdout).unwrap().lines().count());
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(
+        stderr.contains("line 2: expected two words, found 1"),
+        "{}",
+        stderr
+    );
+
+    let out = run_with_stdin(&dir, &["--strict", "-"], input);
+    assert_eq!(Some(1), out.status.code());
+    assert!(out.stdout.is_empty());
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(
+        stderr.contains("line 2: expected two words, found 1"),
+        "{}",
+        stderr
+    );
+    assert!(!stderr.contains("trying"), "{}", stderr);
+}
+
+#[test]
+fn neighbours() {
+    let dir = scratch("neighbours");
+    let out = run(&dir, &["neighbours", "abc", "8"]);
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert!(lines.contains(&"dupl: aabc"), "{}", stdout);
+    assert!(lines.contains(&"rotate: bca"), "{}", stdout);
+    assert!(lines.contains(&"swap: acb bac"), "{}", stdout);
+    assert!(!dir.join("log.log").exists());
+
+    let out = run(&dir, &["neighbours"]);
+    assert_eq!(Some(2), out.status.code());
+}
+
+#[test]
+fn solve_raw() {
+    let dir = scratch("solve-raw");
+    let ladder = |args: &[&str]| {
+        let out = run(&dir, args);
+        assert!(out.status.success(), "{:?}", out);
+        let stdout = String::from_utf8(out.stdout).unwrap();
+        // All but the time taken.
+        let (ladder, _) = stdout.trim_end().rsplit_once(' ').unwrap();
+        ladder.to_string()
+    };
+    let (abc, bda) = (Word::new("abc").as_u64(), Word::new("bda").as_u64());
+    let words = ladder(&["solve", "abc", "bda"]);
+    assert!(words.starts_with("3 abc "), "{}", words);
+    assert_eq!(
+        words,
+        ladder(&["solve", "--raw", &abc.to_string(), &bda.to_string()])
+    );
+
+    let out = run(&dir, &["solve", "--raw", "0", "1"]);
+    assert_eq!(Some(2), out.status.code());
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    assert!(stderr.starts_with("0: not a packed word\n"), "{}", stderr);
+}
+
+#[cfg(feature = "parallel")]
+#[test]
+fn jobs() {
+    let dir = scratch("jobs");
+    let input = "abc bda\nab ba\nhello help\nabc bzz\ncat dog\nab ba\n";
+    // Everything but elapsed_ms, which won't match.
+    let rows = |args: &[&str]| -> Vec<String> {
+        let out = run_with_stdin(&dir, args, input);
+        assert!(out.status.success(), "{:?}", out);
+        String::from_utf8(out.stdout)
+            .unwrap()
+            .lines()
+            .map(|row| {
+                let mut fields: Vec<&str> = row.split(',').collect();
+                fields.remove(4);
+                fields.join(",")
+            })
+            .collect()
+    };
+    let serial = rows(&["--no-cache", "--format", "csv", "-"]);
+    // The header, then the repeated pair only once.
+    assert_eq!(6, serial.len(), "{:?}", serial);
+    let parallel = rows(&["--no-cache", "--format", "csv", "--jobs", "3", "-"]);
+    assert_eq!(serial, parallel);
+    let sorted = rows(&[
+        "--no-cache",
+        "--format",
+        "csv",
+        "--jobs",
+        "2",
+        "--sort-by-steps",
+        "-",
+    ]);
+    assert_eq!(serial.len(), sorted.len());
+}
+
+#[test]
+fn limit() {
+    let dir = scratch("limit");
+    let input = "hello help\nabc bca\nab ba\nabc bca\nabcd bcda\nab bb\n";
+    let out = run_with_stdin(&dir, &["--no-cache", "--limit", "3", "-"], input);
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(3, lines.len(), "{}", stdout);
+    // The repeat goes first, then the shortest words come to the front.
+    assert!(lines[0].starts_with("2 ab --rotate-> ba "), "{}", stdout);
+    assert!(lines[1].starts_with("2 ab --shift:1+-> bb "), "{}", stdout);
+    assert!(lines[2].starts_with("2 abc --rotate-> bca "), "{}", stdout);
+
+    let out = run_with_stdin(&dir, &["--no-cache", "--limit", "10", "-"], input);
+    assert_eq!(5, String::from_utf8(out.stdout).unwrap().lines().count());
+}
+
+#[test]
+fn no_wrap() {
+    let dir = scratch("no-wrap");
+    let out = run_with_stdin(&dir, &["--no-cache", "-"], "a z\n");
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.starts_with("2 a --shift:1--> z "), "{}", stdout);
+    let out = run_with_stdin(&dir, &["--no-cache", "--no-wrap", "-"], "a z\n");
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.starts_with("26 a --shift:1+-> b "), "{}", stdout);
+}
+
+#[test]
+fn repl() {
+    let dir = scratch("repl");
+    let out = run_with_stdin(&dir, &["repl"], "ab ba\nabc bca\nquit\nabc cab\n");
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(2, lines.len(), "{}", stdout);
+    assert!(lines[0].starts_with("2 ab --rotate-> ba "), "{}", stdout);
+    assert!(lines[1].starts_with("2 abc --rotate-> bca "), "{}", stdout);
+}
+
+#[test]
+fn totals() {
+    let dir = scratch("totals");
+    let out = run_with_stdin(&dir, &["--no-cache", "-"], "ab ba\nabc bca\n");
+    assert!(out.status.success());
+    let stderr = String::from_utf8(out.stderr).unwrap();
+    let summary = stderr.lines().last().unwrap();
+    assert!(summary.starts_with("2 pairs: "), "{}", stderr);
+    assert!(
+        summary.contains(" nodes explored, peak frontier "),
+        "{}",
+        stderr
+    );
+
+    let out = run_with_stdin(&dir, &["--no-cache", "--quiet", "-"], "ab ba\n");
+    assert!(out.stderr.is_empty());
+}
+
+#[test]
+fn reverse_output() {
+    let dir = scratch("reverse-output");
+    let out = run_with_stdin(&dir, &["--no-cache", "--reverse-output", "-"], "abc bda\n");
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.starts_with("3 bda <-"), "{}", stdout);
+    assert!(stdout.contains("-- abc "), "{}", stdout);
+
+    let out = run_with_stdin(
+        &dir,
+        &["--no-cache", "--reverse-output", "--format", "json", "-"],
+        "abc bda\n",
+    );
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    assert!(stdout.contains("\"path\":[\"bda\","), "{}", stdout);
+    assert!(stdout.contains(",\"abc\"]"), "{}", stdout);
+}
+
+#[test]
+fn timeout() {
+    let dir = scratch("timeout");
+    let out = run_with_stdin(
+        &dir,
+        &["--no-cache", "--timeout-ms", "0", "--format", "json", "-"],
+        "abc bda\nabc bca\n",
+    );
+    assert!(out.status.success());
+    let stdout = String::from_utf8(out.stdout).unwrap();
+    let lines: Vec<&str> = stdout.lines().collect();
+    assert_eq!(2, lines.len(), "{}", stdout);
+    assert!(
+        lines[0].contains("\"found\":false,\"timed_out\":true"),
+        "{}",
+        stdout
+    );
+    assert!(lines[1].contains("\"found\":true"), "{}", stdout);
+    let log = fs::read_to_string(dir.join("log.log")).unwrap();
+    assert!(log.starts_with("timed out on abc -> bda "), "{}", log);
+}
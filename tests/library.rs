@@ -0,0 +1,19 @@
+use uwcsmini::{format_ladder, solve, Alphabet, Word, DEFAULT_MAX_NODES, DEFAULT_MAX_STEPS};
+
+#[test]
+fn solves_as_a_dependency() {
+    let result = solve(
+        Word::new("ab"),
+        Word::new("ca"),
+        2,
+        Alphabet::default(),
+        DEFAULT_MAX_STEPS,
+        DEFAULT_MAX_NODES,
+    )
+    .unwrap();
+    let (path, ops) = result.into_ladder().unwrap();
+    assert_eq!(
+        "ab --shift:2+-> ac --rotate-> ca",
+        format_ladder(&path, &ops)
+    );
+}
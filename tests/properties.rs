@@ -0,0 +1,93 @@
+use proptest::prelude::*;
+use uwcsmini::{distance, Alphabet, Word};
+
+/// The word must survive a trip through its packed form and its letters, and
+/// have the length the move should have left it.
+fn assert_valid(word: Word, len: usize) {
+    assert_eq!(Some(word), Word::from_raw_checked(word.as_u64()));
+    let letters = word.to_string();
+    assert_eq!(len, letters.len(), "{}", letters);
+    assert_eq!(usize::from(word.len()), len);
+    assert!(
+        letters.bytes().all(|b| b.is_ascii_lowercase()),
+        "{}",
+        letters
+    );
+    assert_eq!(word, Word::new(&letters));
+}
+
+proptest! {
+    #[test]
+    fn packed_shifts(s in "[a-z]{1,6}", size in 1u8..=26) {
+        let word = Word::new(&s);
+        let alphabet = Alphabet::new(size);
+        prop_assert_eq!(word.shifts_in_scalar(alphabet), word.shifts_in(alphabet));
+    }
+
+    #[test]
+    fn reachable_when_found(
+        left in "[a-h]{1,3}",
+        right in "[a-h]{1,3}",
+        len_limit in 1u8..=3,
+        size in 1u8..=8,
+    ) {
+        let (left, right) = (Word::new(&left), Word::new(&right));
+        let alphabet = Alphabet::new(size);
+        if distance(left, right, len_limit, alphabet).is_some() {
+            prop_assert!(left.is_reachable(&right, len_limit, alphabet));
+        }
+    }
+
+    #[test]
+    fn round_trips(s in "[a-z]{1,12}") {
+        let word = Word::new(&s);
+        assert_valid(word, s.len());
+        prop_assert_eq!(&s, &word.to_string());
+    }
+
+    #[test]
+    fn moves_stay_valid(s in "[a-z]{1,12}", code in 1u8..=26, index in 0u8..12) {
+        let word = Word::new(&s);
+        let len = s.len();
+
+        if let Some(w) = word.dupl_first(12) {
+            assert_valid(w, len + 1);
+        }
+        if let Some(w) = word.push(code, 12) {
+            assert_valid(w, len + 1);
+        }
+        if let Some(w) = word.insert_at(index, code, 12) {
+            assert_valid(w, len + 1);
+        }
+        if let Some(w) = word.pop() {
+            assert_valid(w, len - 1);
+        }
+        if let Some(w) = word.pop_last() {
+            assert_valid(w, len - 1);
+        }
+        if let Some(w) = word.remove_at(index) {
+            assert_valid(w, len - 1);
+        }
+        if index < word.len() {
+            assert_valid(word.set(index, code), len);
+        }
+        for w in word.shifts().into_iter().flatten() {
+            assert_valid(w, len);
+        }
+        for w in word.rotate().into_iter().flatten() {
+            assert_valid(w, len);
+        }
+        for w in word.swap_adjacent().into_iter().flatten() {
+            assert_valid(w, len);
+        }
+        assert_valid(word.reverse(), len);
+    }
+
+    #[test]
+    fn only_whole_words_decode(raw in any::<u64>()) {
+        if let Some(word) = Word::from_raw_checked(raw) {
+            prop_assert_eq!(raw, word.as_u64());
+            assert_valid(word, word.to_string().len());
+        }
+    }
+}